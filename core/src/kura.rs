@@ -0,0 +1,85 @@
+//! Storage for committed blocks and the periodic finality justifications built over
+//! them (see [`crate::sumeragi::justification`]). Both are kept in memory, each behind its
+//! own [`Mutex`], for the lifetime of the process; nothing is written to disk. Every method
+//! takes one of those locks, so any call can block the calling thread while another call
+//! holds it; the consensus main loop calls [`Kura::store_justification_blocking`] on its
+//! own thread.
+
+use std::collections::BTreeMap;
+
+use parking_lot::Mutex;
+
+use crate::{sumeragi::justification::CommitJustification, VersionedCommittedBlock};
+
+/// Handle to this peer's block and justification storage. Cheap to share: every method
+/// takes `&self`, so callers hold it behind an `Arc` rather than needing `&mut` access.
+#[derive(Default)]
+pub struct Kura {
+    blocks: Mutex<Vec<VersionedCommittedBlock>>,
+    justifications: Mutex<BTreeMap<u64, CommitJustification>>,
+}
+
+impl Kura {
+    /// Append `block` to the in-memory block list.
+    pub fn store_block_blocking(&self, block: VersionedCommittedBlock) {
+        self.blocks.lock().push(block);
+    }
+
+    /// Every stored block after (not including) the one hashing to `hash`, oldest first.
+    /// Empty if `hash` isn't a block this peer has stored.
+    pub fn blocks_after_hash(
+        &self,
+        hash: iroha_crypto::HashOf<VersionedCommittedBlock>,
+    ) -> Vec<VersionedCommittedBlock> {
+        let blocks = self.blocks.lock();
+        match blocks.iter().position(|block| block.hash() == hash) {
+            Some(index) => blocks[index + 1..].to_vec(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every stored block from `height` (1-indexed) onward, oldest first.
+    pub fn blocks_from_height(&self, height: usize) -> Vec<VersionedCommittedBlock> {
+        self.blocks
+            .lock()
+            .iter()
+            .filter(|block| (block.header().height as usize) >= height)
+            .cloned()
+            .collect()
+    }
+
+    /// Store `justification` in memory, keyed by the height it was assembled at, replacing
+    /// any justification already stored for that height.
+    pub fn store_justification_blocking(&self, justification: CommitJustification) {
+        self.justifications
+            .lock()
+            .insert(justification.height, justification);
+    }
+
+    /// The justification assembled at `height`, if this peer ever stored one.
+    pub fn get_justification_blocking(&self, height: u64) -> Option<CommitJustification> {
+        self.justifications.lock().get(&height).cloned()
+    }
+
+    /// Every stored justification for a height after the block hashing to `block_hash`, in
+    /// increasing height order. Empty if `block_hash` isn't a block this peer has stored.
+    pub fn get_justifications_after_hash_blocking(
+        &self,
+        block_hash: iroha_crypto::HashOf<VersionedCommittedBlock>,
+    ) -> Vec<CommitJustification> {
+        let Some(after_height) = self
+            .blocks
+            .lock()
+            .iter()
+            .find(|block| block.hash() == block_hash)
+            .map(|block| block.header().height)
+        else {
+            return Vec::new();
+        };
+        self.justifications
+            .lock()
+            .range((after_height + 1)..)
+            .map(|(_, justification)| justification.clone())
+            .collect()
+    }
+}
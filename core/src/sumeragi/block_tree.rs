@@ -0,0 +1,171 @@
+//! A lightweight tree of committed-block hashes, kept alongside the single canonical chain
+//! [`block_commit`](super::fault::block_commit) extends by default.
+//!
+//! Ordinarily every [`Message::BlockCommitted`](super::fault::BlockCommitted) a peer accepts
+//! extends [`SumeragiStateMachineData::latest_block_hash`](super::fault::SumeragiStateMachineData::latest_block_hash)
+//! directly. During a partition, though, two sub-quorums can each gather
+//! `min_votes_for_commit` signatures for a different block at the same height, so a peer
+//! can receive a `BlockCommitted` that instead branches off an earlier block. [`BlockTree`]
+//! remembers every committed block this peer has seen (not just the ones on its own
+//! branch), so [`BlockTree::route`] can compute the retract/enact edit needed to switch the
+//! canonical chain from one branch to another, and [`choose`] can decide whether the
+//! competing branch is actually worth switching to.
+
+use std::collections::{HashMap, HashSet};
+
+use iroha_crypto::HashOf;
+
+use crate::VersionedCommittedBlock;
+
+/// A committed block's place in the tree: its parent and the size of the quorum that
+/// committed it, used to weigh one branch against another in [`choose`].
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    parent_hash: HashOf<VersionedCommittedBlock>,
+    quorum_weight: usize,
+}
+
+/// Every committed block this peer has recorded, keyed by hash. Grows without bound for
+/// the lifetime of the process: there is no method that removes a node once it has been
+/// inserted.
+#[derive(Debug, Default)]
+pub struct BlockTree {
+    nodes: HashMap<HashOf<VersionedCommittedBlock>, Node>,
+}
+
+/// The edit needed to move the canonical chain from one branch to another: retract every
+/// block from the old head back to (but not including) `common_ancestor`, then enact every
+/// block from just after `common_ancestor` up to the new head, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeRoute {
+    pub common_ancestor: HashOf<VersionedCommittedBlock>,
+    pub retract: Vec<HashOf<VersionedCommittedBlock>>,
+    pub enact: Vec<HashOf<VersionedCommittedBlock>>,
+}
+
+impl BlockTree {
+    /// Record `block` (already known to have committed under a quorum of `quorum_weight`
+    /// signatures) as a node in the tree.
+    pub fn insert(&mut self, block: &VersionedCommittedBlock, quorum_weight: usize) {
+        self.nodes.insert(
+            block.hash(),
+            Node {
+                parent_hash: block.header().previous_block_hash,
+                quorum_weight,
+            },
+        );
+    }
+
+    /// The quorum weight recorded for `hash`, if this peer has seen it committed.
+    pub fn quorum_weight(&self, hash: HashOf<VersionedCommittedBlock>) -> Option<usize> {
+        self.nodes.get(&hash).map(|node| node.quorum_weight)
+    }
+
+    /// `hash` followed by every ancestor this peer has recorded for it, most recent first.
+    /// Stops as soon as an ancestor isn't in the tree (e.g. it predates this peer joining).
+    fn lineage(&self, hash: HashOf<VersionedCommittedBlock>) -> Vec<HashOf<VersionedCommittedBlock>> {
+        let mut chain = vec![hash];
+        let mut current = hash;
+        while let Some(node) = self.nodes.get(&current) {
+            chain.push(node.parent_hash);
+            current = node.parent_hash;
+        }
+        chain
+    }
+
+    /// Compute the [`TreeRoute`] from `from` to `to`, or `None` if this peer never recorded
+    /// a shared ancestor for the two (so there is no way to know how to splice them).
+    pub fn route(
+        &self,
+        from: HashOf<VersionedCommittedBlock>,
+        to: HashOf<VersionedCommittedBlock>,
+    ) -> Option<TreeRoute> {
+        if from == to {
+            return Some(TreeRoute {
+                common_ancestor: from,
+                retract: Vec::new(),
+                enact: Vec::new(),
+            });
+        }
+
+        let from_lineage = self.lineage(from);
+        let to_lineage = self.lineage(to);
+        let to_ancestors: HashSet<_> = to_lineage.iter().copied().collect();
+
+        let common_ancestor = from_lineage
+            .iter()
+            .copied()
+            .find(|hash| to_ancestors.contains(hash))?;
+
+        let retract = from_lineage
+            .into_iter()
+            .take_while(|hash| *hash != common_ancestor)
+            .collect();
+        let mut enact: Vec<_> = to_lineage
+            .into_iter()
+            .take_while(|hash| *hash != common_ancestor)
+            .collect();
+        enact.reverse(); // oldest-first, so callers can re-apply in chain order
+
+        Some(TreeRoute {
+            common_ancestor,
+            retract,
+            enact,
+        })
+    }
+
+    /// Total quorum weight recorded along `route.enact`, used to compare a competing
+    /// branch against the one it would retract. A hash in `route.enact` with no recorded
+    /// weight (shouldn't happen for a route this tree itself produced) contributes `0`.
+    pub fn enact_weight(&self, route: &TreeRoute) -> usize {
+        route
+            .enact
+            .iter()
+            .filter_map(|hash| self.quorum_weight(*hash))
+            .sum()
+    }
+
+    /// Total quorum weight recorded along `route.retract`.
+    pub fn retract_weight(&self, route: &TreeRoute) -> usize {
+        route
+            .retract
+            .iter()
+            .filter_map(|hash| self.quorum_weight(*hash))
+            .sum()
+    }
+}
+
+/// What to do with an incoming commit that doesn't linearly extend the current head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Switch the canonical chain via `route`: the incoming branch outweighs the one it
+    /// would retract.
+    Reorganize(TreeRoute),
+    /// Keep the current chain: either no route could be computed (the competing branch's
+    /// history is unknown to this peer), or it doesn't outweigh the current one.
+    KeepCurrent,
+}
+
+/// Decide what a peer should do about a `BlockCommitted` whose announced parent
+/// (`candidate_parent_hash`) is not `current_head`, weighing the candidate branch
+/// (`route.enact` plus `candidate_weight` for the incoming block itself, which isn't in the
+/// tree yet) against the branch it would retract (`route.retract`).
+pub fn choose(
+    tree: &BlockTree,
+    current_head: HashOf<VersionedCommittedBlock>,
+    candidate_parent_hash: HashOf<VersionedCommittedBlock>,
+    candidate_weight: usize,
+) -> Decision {
+    let Some(route) = tree.route(current_head, candidate_parent_hash) else {
+        return Decision::KeepCurrent;
+    };
+
+    let candidate_total = tree.enact_weight(&route) + candidate_weight;
+    let current_total = tree.retract_weight(&route);
+
+    if candidate_total > current_total {
+        Decision::Reorganize(route)
+    } else {
+        Decision::KeepCurrent
+    }
+}
@@ -0,0 +1,137 @@
+//! The genesis/fork chain: an append-only record of every validator-set change this
+//! network has gone through via a deliberate hard fork, rather than an ordinary
+//! (soft, same-fork) validator rotation through [`crate::VersionedCommittedBlock`]s.
+//!
+//! Operators use this for controlled chain splits (e.g. an upgrade migration): push a new
+//! [`ForkDescriptor`] onto the [`ForkSet`] and restart every peer with it. Blocks already
+//! committed before the new fork's start height are kept as they are; nothing here prunes
+//! them. Peers send each other their [`ForkSet::hash`] in a `GenesisHandshake` message as
+//! soon as they connect (see [`super::fault`]), and a peer advertising a different hash is
+//! disconnected. A block at the active fork's start height whose parent is not the fork's
+//! recorded parent is rejected by [`check_block_against_active_fork`], and `BFT` state (the view-change proof chain) restarts at zero at each fork boundary, so
+//! no quorum certificate from before the fork can be replayed as evidence on the new one.
+
+use iroha_crypto::HashOf;
+use iroha_data_model::prelude::PeerId;
+use parity_scale_codec::{Decode, Encode};
+
+use crate::VersionedCommittedBlock;
+
+/// One entry in a [`ForkSet`]: the validator set active on a single fork, and where that
+/// fork's first block attaches.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ForkDescriptor {
+    /// Height of this fork's first block.
+    pub first_block_height: u64,
+    /// Hash of the block this fork's first block chains onto: the last block of the
+    /// previous fork, or the zeroed hash if this is the network's original fork.
+    pub first_block_parent_hash: HashOf<VersionedCommittedBlock>,
+    /// Validator set active on this fork from `first_block_height` onward.
+    pub validators: Vec<PeerId>,
+    /// Hash of every entry in the [`ForkSet`] preceding this one, so a peer can verify it
+    /// agrees on the fork history without having to re-ship every past entry.
+    pub prior_forks_hash: HashOf<ForkSet>,
+}
+
+/// Reasons [`ForkSet::try_push`] can refuse to extend the chain.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ForkError {
+    /// `prior_forks_hash` doesn't match the hash of the chain it's meant to extend.
+    #[error("fork descriptor's prior_forks_hash does not match the chain it extends")]
+    PriorForksHashMismatch,
+    /// `validators` is empty; a fork with no validators could never commit a block.
+    #[error("fork descriptor has an empty validator set")]
+    EmptyValidatorSet,
+}
+
+/// Reasons [`check_block_against_active_fork`] can reject a block.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ForkConsistencyError {
+    /// `block`'s height is the active fork's `first_block_height`, but its parent hash
+    /// doesn't match `first_block_parent_hash`.
+    #[error(
+        "block at the active fork's start height {height} has parent hash {actual}, \
+         expected {expected} per the fork descriptor"
+    )]
+    WrongForkStartParent {
+        height: u64,
+        actual: HashOf<VersionedCommittedBlock>,
+        expected: HashOf<VersionedCommittedBlock>,
+    },
+}
+
+/// The append-only chain of every fork this network has gone through, from its original
+/// fork to the active one. A [`ForkSet`] with no entries means the network has never been
+/// forked; every block in the chain belongs to the same, implicit original fork.
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct ForkSet(Vec<ForkDescriptor>);
+
+impl ForkSet {
+    /// The fork chain of a network that has never been forked.
+    pub fn none() -> Self {
+        Self(Vec::new())
+    }
+
+    /// The currently active fork, or `None` if the network has never been forked.
+    pub fn active(&self) -> Option<&ForkDescriptor> {
+        self.0.last()
+    }
+
+    /// Hash of the whole chain, used as the handshake value peers compare to decide whether
+    /// they're on the same fork (see the module docs).
+    pub fn hash(&self) -> HashOf<Self> {
+        HashOf::new(self)
+    }
+
+    /// Append `descriptor` to the chain, checking that it actually extends it.
+    ///
+    /// # Errors
+    /// See [`ForkError`].
+    pub fn try_push(&self, descriptor: ForkDescriptor) -> Result<Self, ForkError> {
+        if descriptor.validators.is_empty() {
+            return Err(ForkError::EmptyValidatorSet);
+        }
+        if descriptor.prior_forks_hash != self.hash() {
+            return Err(ForkError::PriorForksHashMismatch);
+        }
+        let mut extended = self.0.clone();
+        extended.push(descriptor);
+        Ok(Self(extended))
+    }
+}
+
+/// Reject `block` if it is inconsistent with `fork_set`'s active fork: landing exactly at
+/// the active fork's start height with a different parent than
+/// [`ForkDescriptor::first_block_parent_hash`] promises. Heights before or after the fork
+/// boundary are unaffected; ordinary (same-fork) parent-hash chaining is already checked
+/// elsewhere in the commit path.
+///
+/// # Errors
+/// See [`ForkConsistencyError`].
+pub(super) fn check_block_against_active_fork(
+    fork_set: &ForkSet,
+    block: &VersionedCommittedBlock,
+) -> Result<(), ForkConsistencyError> {
+    let Some(active) = fork_set.active() else {
+        return Ok(());
+    };
+    let height = block.header().height;
+    if height == active.first_block_height
+        && block.header().previous_block_hash != active.first_block_parent_hash
+    {
+        return Err(ForkConsistencyError::WrongForkStartParent {
+            height,
+            actual: block.header().previous_block_hash,
+            expected: active.first_block_parent_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Whether `height` is the first block of `fork_set`'s active fork, i.e. the point at which
+/// BFT state (view-change proof chain) must restart at zero.
+pub(super) fn crosses_fork_boundary(fork_set: &ForkSet, height: u64) -> bool {
+    fork_set
+        .active()
+        .map_or(false, |active| active.first_block_height == height)
+}
@@ -0,0 +1,293 @@
+//! Import-queue subsystem, with two independent halves sharing one worker-thread-plus-
+//! channel shape:
+//!
+//! - [`ImportQueueHandle`]/[`spawn`] serve block-sync queries (blocks after a hash, blocks
+//!   from a height), independently of the consensus thread's `wsv` lock. Every
+//!   [`Sumeragi::blocks_after_hash`]/[`Sumeragi::blocks_from_height`] call used to grab
+//!   that lock, which meant serving a sync request and producing a block contended for the
+//!   same lock; this removes that head-of-line blocking by owning its own read path over
+//!   [`Kura`]. Queries reach the worker over an unbounded channel. This half only answers
+//!   queries; it does not receive incoming blocks or pass them on to Sumeragi.
+//! - [`ImportQueueService`]/[`spawn_import_queue_service`] take block *application* off
+//!   the consensus thread: `import_block` hands a validated block to a worker that
+//!   applies it to its own [`WorldStateView`] and persists it via [`Kura`], so neither
+//!   the ISI execution nor the disk IO stalls consensus. `poll_outcome` is non-blocking,
+//!   so the main loop can queue several blocks ahead during catch-up instead of waiting
+//!   for each one to land before producing or voting on the next.
+//!
+//! [`Sumeragi::blocks_after_hash`]: super::Sumeragi::blocks_after_hash
+//! [`Sumeragi::blocks_from_height`]: super::Sumeragi::blocks_from_height
+
+use std::sync::{mpsc, Arc};
+
+use iroha_crypto::HashOf;
+
+use super::fork::{self, ForkSet};
+use crate::{kura::Kura, prelude::*, VersionedCommittedBlock, VersionedValidBlock};
+
+/// A query the import-queue worker thread knows how to answer.
+enum Request {
+    BlocksAfterHash {
+        hash: HashOf<VersionedCommittedBlock>,
+        respond_to: mpsc::Sender<Vec<VersionedCommittedBlock>>,
+    },
+    BlocksFromHeight {
+        height: usize,
+        respond_to: mpsc::Sender<Vec<VersionedCommittedBlock>>,
+    },
+}
+
+/// Handle to the block-sync query worker started by [`spawn`]. [`super::Sumeragi`] keeps
+/// its handle in a private field and answers its own `blocks_after_hash`/
+/// `blocks_from_height` through it; it does not hand the handle out.
+#[derive(Clone, Debug)]
+pub struct ImportQueueHandle {
+    requests: mpsc::Sender<Request>,
+}
+
+impl ImportQueueHandle {
+    /// See [`super::Sumeragi::blocks_after_hash`].
+    #[allow(clippy::missing_panics_doc)]
+    pub fn blocks_after_hash(
+        &self,
+        hash: HashOf<VersionedCommittedBlock>,
+    ) -> Vec<VersionedCommittedBlock> {
+        let (respond_to, response) = mpsc::channel();
+        if self
+            .requests
+            .send(Request::BlocksAfterHash { hash, respond_to })
+            .is_err()
+        {
+            return Vec::new();
+        }
+        response.recv().unwrap_or_default()
+    }
+
+    /// See [`super::Sumeragi::blocks_from_height`].
+    pub fn blocks_from_height(&self, height: usize) -> Vec<VersionedCommittedBlock> {
+        let (respond_to, response) = mpsc::channel();
+        if self
+            .requests
+            .send(Request::BlocksFromHeight {
+                height,
+                respond_to,
+            })
+            .is_err()
+        {
+            return Vec::new();
+        }
+        response.recv().unwrap_or_default()
+    }
+}
+
+/// Spawn the import-queue worker thread and return a handle to it.
+///
+/// The worker owns `kura` for reads and never touches the consensus thread's `wsv`
+/// lock, so serving sync requests can never block block production (or vice versa).
+#[allow(clippy::expect_used)]
+pub fn spawn(kura: Arc<Kura>) -> ImportQueueHandle {
+    let (requests, incoming) = mpsc::channel::<Request>();
+
+    std::thread::Builder::new()
+        .name("import queue".to_owned())
+        .spawn(move || {
+            for request in incoming {
+                match request {
+                    Request::BlocksAfterHash { hash, respond_to } => {
+                        let _ = respond_to.send(kura.blocks_after_hash(hash));
+                    }
+                    Request::BlocksFromHeight {
+                        height,
+                        respond_to,
+                    } => {
+                        let _ = respond_to.send(kura.blocks_from_height(height));
+                    }
+                }
+            }
+        })
+        .expect("Import queue thread spawn should not fail.");
+
+    ImportQueueHandle { requests }
+}
+
+/// Something submitted to [`ImportQueueService`]'s worker. Requests are processed strictly
+/// in submission order, which is what lets [`ImportQueueService::reorganize`] safely queue a
+/// retraction ahead of the replacement block that should land on top of it.
+enum ImportRequest {
+    /// Apply `block`, tagged with the submitter's [`ForkSet`] so the worker can reject it if
+    /// it turns out to be inconsistent with the active fork (see
+    /// [`fork::check_block_against_active_fork`]).
+    Apply {
+        block: VersionedValidBlock,
+        fork_set: ForkSet,
+    },
+    /// Roll `hashes` back off the worker's `wsv`, most-recent-first, before continuing to
+    /// apply whatever is queued after it. See [`super::block_tree`] for how a competing,
+    /// higher-quorum branch is chosen; this is the only part of executing that choice the
+    /// worker (the sole owner of the canonical `wsv`) can carry out.
+    Retract {
+        hashes: Vec<HashOf<VersionedCommittedBlock>>,
+    },
+    /// Replace the worker's `wsv` outright with `wsv`, ahead of whatever is queued after it.
+    /// The worker's `wsv` is seeded once at [`spawn_import_queue_service`] and otherwise only
+    /// ever evolves through [`Self::Apply`]/[`Self::Retract`], so anything that advances
+    /// `state_machine`'s `wsv` by some other path (genesis commit, snapshot sync) must push
+    /// the result here too, or the worker's copy falls permanently behind and every
+    /// subsequent [`Self::Apply`] fails to apply against it.
+    Resync { wsv: WorldStateView },
+}
+
+/// Result of applying an [`ImportRequest`], reported back through
+/// [`ImportQueueService::poll_outcome`].
+pub enum ImportOutcome {
+    /// `block` applied cleanly; `wsv` is the worker's resulting state, ready to be
+    /// published as the new public-facing [`WorldStateView`].
+    Applied {
+        block: VersionedCommittedBlock,
+        wsv: WorldStateView,
+    },
+    /// `block` failed to apply. This should not happen for a block that already passed
+    /// consensus, so the caller should treat it as a sign of WSV divergence rather than
+    /// something to route around.
+    Rejected { height: u64, reason: String },
+    /// `block` was inconsistent with the submitter's active fork (it sits at the fork's
+    /// start height but its parent hash is not the fork's recorded parent) and was never
+    /// applied.
+    RejectedFork {
+        height: u64,
+        reason: fork::ForkConsistencyError,
+    },
+    /// The requested [`ImportRequest::Retract`] completed; `wsv` is the worker's state with
+    /// `retracted_count` blocks rolled back, ready to be published same as after an
+    /// [`Self::Applied`].
+    Retracted {
+        retracted_count: usize,
+        wsv: WorldStateView,
+    },
+}
+
+/// Handle to the block-application worker thread spawned by
+/// [`spawn_import_queue_service`]. Decouples ISI execution and [`Kura`] persistence from
+/// the consensus thread: [`Self::import_block`] hands off a block and returns immediately,
+/// and [`Self::poll_outcome`] is non-blocking so the main loop can keep voting on or
+/// producing further blocks while earlier ones are still being applied.
+pub struct ImportQueueService {
+    requests: mpsc::Sender<ImportRequest>,
+    outcomes: mpsc::Receiver<ImportOutcome>,
+}
+
+impl ImportQueueService {
+    /// Submit `block` for application on the worker thread, checked against `fork_set`
+    /// (the submitter's active fork) before being applied. Non-blocking; the outcome
+    /// arrives later via [`Self::poll_outcome`], in submission order.
+    #[allow(clippy::expect_used)]
+    pub fn import_block(&self, block: VersionedValidBlock, fork_set: ForkSet) {
+        self.requests
+            .send(ImportRequest::Apply { block, fork_set })
+            .expect("Import queue worker thread should not have stopped while handle is alive.");
+    }
+
+    /// Submit a retraction of `hashes` (most-recent-first) ahead of whatever is queued
+    /// after it, so a subsequent [`Self::import_block`] call lands on the rolled-back
+    /// state instead of racing it. See [`super::block_tree::choose`] for how `hashes` is
+    /// decided.
+    #[allow(clippy::expect_used)]
+    pub fn reorganize(&self, hashes: Vec<HashOf<VersionedCommittedBlock>>) {
+        self.requests
+            .send(ImportRequest::Retract { hashes })
+            .expect("Import queue worker thread should not have stopped while handle is alive.");
+    }
+
+    /// Replace the worker's `wsv` with `wsv`, ahead of anything queued after it. Callers
+    /// that advance `state_machine`'s `wsv` by some path other than [`Self::import_block`]
+    /// (genesis commit, snapshot sync) must call this immediately afterward so the worker's
+    /// independent copy doesn't fall behind.
+    #[allow(clippy::expect_used)]
+    pub fn resync(&self, wsv: WorldStateView) {
+        self.requests
+            .send(ImportRequest::Resync { wsv })
+            .expect("Import queue worker thread should not have stopped while handle is alive.");
+    }
+
+    /// Non-blocking check for the next completed [`ImportOutcome`], if any.
+    pub fn poll_outcome(&self) -> Option<ImportOutcome> {
+        match self.outcomes.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                panic!("Import queue worker thread should not have stopped while handle is alive.")
+            }
+        }
+    }
+}
+
+/// Spawn the block-application worker thread and return a handle to it.
+///
+/// The worker owns its own `wsv` (seeded from the caller's current state) and `kura`, and
+/// applies/persists submitted blocks on it one at a time, in submission order, entirely off
+/// the consensus thread.
+#[allow(clippy::expect_used)]
+pub fn spawn_import_queue_service(wsv: WorldStateView, kura: Arc<Kura>) -> ImportQueueService {
+    let (requests, incoming) = mpsc::channel::<ImportRequest>();
+    let (outcomes_sender, outcomes) = mpsc::channel::<ImportOutcome>();
+
+    std::thread::Builder::new()
+        .name("import queue service".to_owned())
+        .spawn(move || {
+            let mut wsv = wsv;
+            for request in incoming {
+                match request {
+                    ImportRequest::Apply { block, fork_set } => {
+                        let block = block.commit();
+                        let height = block.header().height;
+
+                        if let Err(reason) =
+                            fork::check_block_against_active_fork(&fork_set, &block)
+                        {
+                            let _ = outcomes_sender
+                                .send(ImportOutcome::RejectedFork { height, reason });
+                            continue;
+                        }
+
+                        match wsv.apply(block.clone()) {
+                            Ok(()) => {
+                                kura.store_block_blocking(block.clone());
+                                let _ = outcomes_sender.send(ImportOutcome::Applied {
+                                    block,
+                                    wsv: wsv.clone(),
+                                });
+                            }
+                            Err(error) => {
+                                let _ = outcomes_sender.send(ImportOutcome::Rejected {
+                                    height,
+                                    reason: error.to_string(),
+                                });
+                            }
+                        }
+                    }
+                    ImportRequest::Retract { hashes } => {
+                        let retracted_count = hashes.len();
+                        for hash in hashes {
+                            wsv.retract_block(hash).expect(
+                                "Retracting a block this worker itself previously applied \
+                                 should not fail.",
+                            );
+                        }
+                        let _ = outcomes_sender.send(ImportOutcome::Retracted {
+                            retracted_count,
+                            wsv: wsv.clone(),
+                        });
+                    }
+                    ImportRequest::Resync { wsv: resynced } => {
+                        wsv = resynced;
+                    }
+                }
+            }
+        })
+        .expect("Import queue service thread spawn should not fail.");
+
+    ImportQueueService {
+        requests,
+        outcomes,
+    }
+}
@@ -0,0 +1,127 @@
+//! Periodic finality justifications: small, independently-verifiable proofs that a
+//! checkpoint block was committed by a supermajority of the then-current topology.
+//!
+//! A catching-up peer can verify only the justified checkpoints — each carrying at
+//! least `2f+1` signatures over its block hash plus the committing [`Topology`] — and
+//! treat the blocks in between as implied, instead of re-validating the full chain.
+//!
+//! The committing peer broadcasts each justification it assembles (`Message::Justification`
+//! in [`super::fault`]) so every peer, not just the one that happened to commit the
+//! checkpoint, ends up with it persisted and servable through [`super::Sumeragi::justification_for_height`].
+
+use iroha_crypto::{HashOf, SignatureOf};
+
+use super::{
+    fault::{FaultInjection, SumeragiWithFault},
+    network_topology::Topology,
+};
+use crate::VersionedCommittedBlock;
+
+/// Aggregated proof that a block was committed by more than the fault threshold of the
+/// topology that was active at the time.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CommitJustification {
+    /// Height of the justified block.
+    pub height: u64,
+    /// Hash of the justified block.
+    pub block_hash: HashOf<VersionedCommittedBlock>,
+    /// Topology that was active when the block was committed, needed to re-verify
+    /// `signatures` without access to the live chain.
+    pub topology: Topology,
+    /// Signatures over `block_hash`, one per signing validator, all distinct.
+    pub signatures: Vec<SignatureOf<VersionedCommittedBlock>>,
+}
+
+/// Reasons [`CommitJustification::verify`] can reject a justification.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum JustificationError {
+    /// A signature doesn't belong to any peer in the embedded topology.
+    #[error("justification contains a signature not made by a peer in its topology")]
+    UnknownSignatory,
+    /// Two or more signatures are from the same signatory.
+    #[error("justification contains duplicate signatures from the same signatory")]
+    DuplicateSignatory,
+    /// Fewer than `2f+1` distinct, valid signatures were present.
+    #[error("justification has {actual} signature(s), need at least {threshold}")]
+    NotEnoughSignatures {
+        /// Number of distinct valid signatures found.
+        actual: usize,
+        /// The topology's `min_votes_for_commit` (`2f+1`).
+        threshold: usize,
+    },
+}
+
+impl CommitJustification {
+    /// Re-derive this justification from a freshly committed block, its height and the
+    /// topology that committed it.
+    pub fn new(
+        height: u64,
+        block_hash: HashOf<VersionedCommittedBlock>,
+        topology: Topology,
+        signatures: Vec<SignatureOf<VersionedCommittedBlock>>,
+    ) -> Self {
+        Self {
+            height,
+            block_hash,
+            topology,
+            signatures,
+        }
+    }
+
+    /// Check that `self.signatures` are all made by distinct peers of `self.topology`
+    /// and that there are at least as many of them as the topology's
+    /// `min_votes_for_commit`.
+    ///
+    /// # Errors
+    /// See [`JustificationError`].
+    pub fn verify(&self) -> Result<(), JustificationError> {
+        let topology_keys: std::collections::HashSet<_> = self
+            .topology
+            .sorted_peers()
+            .iter()
+            .map(|peer| &peer.public_key)
+            .collect();
+
+        let mut seen = std::collections::HashSet::with_capacity(self.signatures.len());
+        for signature in &self.signatures {
+            let signatory = signature.public_key();
+            if !topology_keys.contains(signatory) {
+                return Err(JustificationError::UnknownSignatory);
+            }
+            if !seen.insert(signatory) {
+                return Err(JustificationError::DuplicateSignatory);
+            }
+        }
+
+        let threshold = self.topology.min_votes_for_commit();
+        if seen.len() < threshold {
+            return Err(JustificationError::NotEnoughSignatures {
+                actual: seen.len(),
+                threshold,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Assemble a [`CommitJustification`] for `block` if `height` falls on a
+/// `justification_period` boundary, otherwise return `None`.
+pub(super) fn maybe_justify<F: FaultInjection>(
+    sumeragi: &SumeragiWithFault<F>,
+    height: u64,
+    block_hash: HashOf<VersionedCommittedBlock>,
+    topology: &Topology,
+    signatures: Vec<SignatureOf<VersionedCommittedBlock>>,
+) -> Option<CommitJustification> {
+    let period = sumeragi.justification_period;
+    if period == 0 || height % period != 0 {
+        return None;
+    }
+    Some(CommitJustification::new(
+        height,
+        block_hash,
+        topology.clone(),
+        signatures,
+    ))
+}
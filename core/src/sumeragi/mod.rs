@@ -7,7 +7,7 @@
     clippy::std_instead_of_alloc
 )]
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fmt::{self, Debug, Formatter},
     marker::PhantomData,
     sync::Arc,
@@ -22,21 +22,28 @@ use iroha_data_model::prelude::*;
 use iroha_logger::prelude::*;
 use iroha_p2p::{ConnectPeer, DisconnectPeer};
 use network_topology::{Role, Topology};
+use rand::SeedableRng;
 
 use crate::{genesis::GenesisNetwork, handler::ThreadHandler};
 
+pub mod block_tree;
 pub mod fault;
+pub mod fork;
+pub mod import_queue;
+pub mod justification;
 pub mod message;
 pub mod network_topology;
+pub mod snapshot_sync;
+pub mod sync_events;
+pub mod tracked_mutex;
 pub mod view_change;
 
-use std::sync::Mutex;
-
 use fault::SumeragiStateMachineData;
 
 use self::{
     fault::{NoFault, SumeragiWithFault},
     message::{Message, *},
+    tracked_mutex::{TrackedMutex, TrackedRwLock},
     view_change::{Proof, ProofChain as ViewChangeProofs},
 };
 use crate::{
@@ -59,6 +66,7 @@ trait Consensus {
 #[derive(Debug)]
 pub struct Sumeragi {
     internal: SumeragiWithFault<NoFault>,
+    import_queue: import_queue::ImportQueueHandle,
 }
 
 impl Sumeragi {
@@ -85,6 +93,8 @@ impl Sumeragi {
 
         let sumeragi_state_machine_data = SumeragiStateMachineData {
             genesis_network,
+            fork_set: fork::ForkSet::none(),
+            block_tree: block_tree::BlockTree::default(),
             latest_block_hash: Hash::zeroed().typed(),
             latest_block_height: 0,
             current_topology: network_topology,
@@ -95,33 +105,78 @@ impl Sumeragi {
             sumeragi_thread_should_exit: false,
         };
 
-        let (incoming_message_sender, incoming_message_receiver) =
-            std::sync::mpsc::sync_channel(250);
+        let (incoming_message_sender, incoming_message_receiver) = tokio::sync::mpsc::channel(250);
+
+        let import_queue = import_queue::spawn(Arc::clone(&kura));
+        let block_import =
+            import_queue::spawn_import_queue_service(wsv.clone(), Arc::clone(&kura));
+        let sync_events_sender = sync_events::channel();
 
         Ok(Self {
             internal: SumeragiWithFault::<NoFault> {
                 key_pair: configuration.key_pair.clone(),
                 peer_id: configuration.peer_id.clone(),
                 events_sender,
-                wsv: std::sync::Mutex::new(wsv),
+                wsv: TrackedRwLock::new("wsv", tracked_mutex::lock_order::WSV, wsv),
                 commit_time: Duration::from_millis(configuration.commit_time_limit_ms),
                 block_time: Duration::from_millis(configuration.block_time_ms),
+                view_change_timeout_delta: Duration::from_millis(
+                    configuration.view_change_timeout_delta_ms,
+                ),
+                view_change_timeout_cap: configuration.view_change_timeout_cap,
+                max_forward_time_drift: Duration::from_millis(
+                    configuration.max_forward_time_drift_ms,
+                ),
+                justification_period: configuration.justification_period,
                 transaction_limits: configuration.transaction_limits,
                 transaction_validator,
                 queue,
                 broker,
                 kura,
+                block_import,
                 network,
                 fault_injection: PhantomData,
                 gossip_batch_size: configuration.gossip_batch_size,
                 gossip_period: Duration::from_millis(configuration.gossip_period_ms),
-
-                sumeragi_state_machine_data: Mutex::new(sumeragi_state_machine_data),
-                current_online_peers: Mutex::new(Vec::new()),
-                latest_block_hash_for_use_by_block_sync: Mutex::new(Hash::zeroed().typed()),
-                incoming_message_sender: Mutex::new(incoming_message_sender),
-                incoming_message_receiver: Mutex::new(incoming_message_receiver),
+                expected_genesis_hash: configuration.expected_genesis_hash,
+                expected_genesis_validators: configuration.expected_genesis_validators.clone(),
+
+                sumeragi_state_machine_data: TrackedMutex::new(
+                    "sumeragi_state_machine_data",
+                    tracked_mutex::lock_order::SUMERAGI_STATE_MACHINE_DATA,
+                    sumeragi_state_machine_data,
+                ),
+                current_online_peers: TrackedMutex::new(
+                    "current_online_peers",
+                    tracked_mutex::lock_order::CURRENT_ONLINE_PEERS,
+                    Vec::new(),
+                ),
+                peer_block_heights: TrackedMutex::new(
+                    "peer_block_heights",
+                    tracked_mutex::lock_order::PEER_BLOCK_HEIGHTS,
+                    HashMap::new(),
+                ),
+                latest_block_hash_for_use_by_block_sync: TrackedMutex::new(
+                    "latest_block_hash_for_use_by_block_sync",
+                    tracked_mutex::lock_order::LATEST_BLOCK_HASH_FOR_USE_BY_BLOCK_SYNC,
+                    Hash::zeroed().typed(),
+                ),
+                incoming_message_sender: TrackedMutex::new(
+                    "incoming_message_sender",
+                    tracked_mutex::lock_order::INCOMING_MESSAGE_SENDER,
+                    incoming_message_sender,
+                ),
+                incoming_message_receiver: TrackedMutex::new(
+                    "incoming_message_receiver",
+                    tracked_mutex::lock_order::INCOMING_MESSAGE_RECEIVER,
+                    incoming_message_receiver,
+                ),
+                sync_events_sender,
+                rng: parking_lot::Mutex::new(rand::rngs::StdRng::seed_from_u64(
+                    configuration.fault_injection_seed,
+                )),
             },
+            import_queue,
         })
     }
 
@@ -143,11 +198,7 @@ impl Sumeragi {
             .try_into()
             .expect("casting usize to u64");
 
-        let wsv_guard = self
-            .internal
-            .wsv
-            .lock()
-            .expect("Failed to lock on `update_metrics`. Mutex poisoned");
+        let wsv_guard = self.internal.wsv_read();
 
         #[allow(clippy::cast_possible_truncation)]
         if let Some(timestamp) = wsv_guard.genesis_timestamp() {
@@ -183,26 +234,67 @@ impl Sumeragi {
 
     /// Get an array of blocks after the block identified by `block_hash`. Returns
     /// an empty array if the specified block could not be found.
-    #[allow(clippy::expect_used)]
+    ///
+    /// Served by the [`import_queue`] worker rather than the consensus thread's `wsv`,
+    /// so a sync request can never block block production (or vice versa).
     pub fn blocks_after_hash(
         &self,
         block_hash: HashOf<VersionedCommittedBlock>,
     ) -> Vec<VersionedCommittedBlock> {
-        self.internal
-            .wsv
-            .lock()
-            .expect("Mutex on internal WSV poisoned in `blocks_after_hash`")
-            .blocks_after_hash(block_hash)
+        self.import_queue.blocks_after_hash(block_hash)
     }
 
     /// Get an array of blocks from `block_height`. (`blocks[block_height]`, `blocks[block_height + 1]` etc.)
-    #[allow(clippy::expect_used)]
+    ///
+    /// Served by the [`import_queue`] worker rather than the consensus thread's `wsv`,
+    /// so a sync request can never block block production (or vice versa).
     pub fn blocks_from_height(&self, block_height: usize) -> Vec<VersionedCommittedBlock> {
+        self.import_queue.blocks_from_height(block_height)
+    }
+
+    /// Get the finality justification for the block at `height`, if one was assembled
+    /// (i.e. `height` fell on a `justification_period` boundary).
+    pub fn justification_for_height(&self, height: u64) -> Option<justification::CommitJustification> {
+        self.internal.kura.get_justification_blocking(height)
+    }
+
+    /// Get all finality justifications assembled after the block identified by
+    /// `block_hash`, in increasing height order.
+    pub fn justifications_after_hash(
+        &self,
+        block_hash: HashOf<VersionedCommittedBlock>,
+    ) -> Vec<justification::CommitJustification> {
+        self.internal.kura.get_justifications_after_hash_blocking(block_hash)
+    }
+
+    /// Hash of the fork chain this peer currently believes it is on (see [`fork`]).
+    /// Compared during the p2p handshake so peers on different forks refuse to gossip.
+    #[allow(clippy::expect_used)]
+    pub fn fork_set_hash(&self) -> HashOf<fork::ForkSet> {
         self.internal
-            .wsv
+            .sumeragi_state_machine_data
+            .lock()
+            .expect("lock on state machine data for fork_set_hash")
+            .fork_set
+            .hash()
+    }
+
+    /// Push a new entry onto the active fork chain, letting operators perform a
+    /// controlled hard fork (e.g. an upgrade migration): once the committed chain reaches
+    /// `descriptor.first_block_height`, this peer switches to the new fork's validator set
+    /// and restarts its view-change proof chain from zero.
+    ///
+    /// # Errors
+    /// See [`fork::ForkError`].
+    #[allow(clippy::expect_used)]
+    pub fn push_fork(&self, descriptor: fork::ForkDescriptor) -> Result<(), fork::ForkError> {
+        let mut state_machine = self
+            .internal
+            .sumeragi_state_machine_data
             .lock()
-            .expect("Mutex on internal WSV poisoned in `blocks_from_height`.")
-            .blocks_from_height(block_height)
+            .expect("lock on state machine data for push_fork");
+        state_machine.fork_set = state_machine.fork_set.try_push(descriptor)?;
+        Ok(())
     }
 
     /// Get a random online peer for use in block synchronization.
@@ -228,17 +320,63 @@ impl Sumeragi {
         }
     }
 
-    /// Access the world state view object in a locking fashion.
-    /// If you intend to do anything substantial you should clone
-    /// and release the lock. This is because no blocks can be produced
-    /// while this lock is held.
-    // TODO: Return result.
+    /// Record `peer`'s self-advertised latest block height, consulted by
+    /// [`Self::get_random_peer_with_blocks_after`] so block sync doesn't waste a round
+    /// trip asking a peer for history it doesn't have.
     #[allow(clippy::expect_used)]
-    pub fn wsv_mutex_access(&self) -> std::sync::MutexGuard<WorldStateView> {
+    pub fn update_peer_block_height(&self, peer: PublicKey, height: u64) {
         self.internal
-            .wsv
+            .peer_block_heights
             .lock()
-            .expect("World state view Mutex access failed")
+            .expect("lock on peer block heights")
+            .insert(peer, height);
+    }
+
+    /// Get a random online peer known to have blocks at or after `block_height`. Falls
+    /// back to [`Self::get_random_peer_for_block_sync`]'s uniform random selection when
+    /// no online peer has an advertised height covering the request.
+    #[allow(clippy::expect_used, clippy::unwrap_in_result)]
+    pub fn get_random_peer_with_blocks_after(&self, block_height: u64) -> Option<Peer> {
+        use rand::RngCore;
+
+        let online_peers = self
+            .internal
+            .current_online_peers
+            .lock()
+            .expect("lock on online peers for get random peer");
+        let heights = self
+            .internal
+            .peer_block_heights
+            .lock()
+            .expect("lock on peer block heights for get random peer");
+        let peers = online_peers
+            .iter()
+            .filter(|peer| {
+                heights
+                    .get(&peer.public_key)
+                    .map_or(false, |&height| height >= block_height)
+            })
+            .map(|peer| Peer::new((*peer).clone()))
+            .collect::<Vec<Peer>>();
+        drop(heights);
+        drop(online_peers);
+
+        if peers.is_empty() {
+            return self.get_random_peer_for_block_sync();
+        }
+
+        let mut sorted_peers = peers;
+        sorted_peers.sort();
+        let index = self.internal.rng.lock().next_u32() as usize % sorted_peers.len();
+        Some(sorted_peers[index].clone())
+    }
+
+    /// Access the world state view object as a shared read guard. Any number of callers
+    /// may hold this concurrently; it only ever blocks for the instant the consensus thread
+    /// (`block_commit` or `finish_queued_commits`) promotes its upgradable-read guard to
+    /// publish a new state.
+    pub fn wsv_mutex_access(&self) -> tracked_mutex::TrackedRwLockReadGuard<'_, WorldStateView> {
+        self.internal.wsv_read()
     }
 
     /// Start the sumeragi thread for this sumeragi instance.
@@ -272,14 +410,26 @@ impl Sumeragi {
         ThreadHandler::new(Box::new(shutdown), thread_handle)
     }
 
-    /// Update the sumeragi internal online peers list.
+    /// Update the sumeragi internal online peers list, emitting a
+    /// [`sync_events::SyncEvent`] for every peer that joined or left rather than
+    /// silently replacing the list.
     #[allow(clippy::expect_used)]
     pub fn update_online_peers(&self, online_peers: Vec<PeerId>) {
-        *self
-            .internal
-            .current_online_peers
-            .lock()
-            .expect("Failed to lock on update online peers.") = online_peers;
+        sync_events::diff_and_replace(
+            &mut self
+                .internal
+                .current_online_peers
+                .lock()
+                .expect("Failed to lock on update online peers."),
+            online_peers,
+            &self.internal.sync_events_sender,
+        );
+    }
+
+    /// Subscribe to connectivity transitions in the online-peer set. See
+    /// [`sync_events::SyncEvent`].
+    pub fn subscribe_to_sync_events(&self) -> sync_events::SyncEventReceiver {
+        self.internal.sync_events_sender.subscribe()
     }
 
     /// Deposit a sumeragi network message.
@@ -0,0 +1,324 @@
+//! Snapshot-based fast state sync. A peer that joins with `latest_block_height == 0`
+//! would otherwise have to replay every committed block to catch up; this subsystem lets
+//! it instead fetch a chunked, hash-verified snapshot of an already-synced peer's
+//! [`WorldStateView`] and replay only the handful of blocks committed after it.
+//!
+//! The flow is request/response over the existing `Message` gossip channel, not a
+//! dedicated connection: a catching-up peer asks a random online peer for a
+//! [`SnapshotManifest`], then fetches the manifest's parts in parallel from whichever
+//! online peers answer, re-requesting from a different peer whenever a part's hash
+//! doesn't match what the manifest promised.
+
+use iroha_crypto::Hash;
+use iroha_p2p::Post;
+use parity_scale_codec::{Decode, Encode};
+use rand::seq::SliceRandom;
+use tokio::sync::mpsc;
+
+use super::{fault::FaultInjection, *};
+
+/// Target size of one [`SnapshotPart`], chosen so a part comfortably fits in a single p2p
+/// message instead of needing its own fragmentation layer.
+const SNAPSHOT_PART_SIZE_BYTES: usize = 1024 * 1024;
+
+/// How long [`try_sync_from_snapshot`] waits for a manifest/part before giving up on a
+/// peer (manifest) or the whole attempt (no online peer has offered anything) and letting
+/// the caller fall back to genesis listening / full block replay.
+const SNAPSHOT_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Payload of the `Message::SnapshotManifestRequest` variant: ask `requested_by`'s peer
+/// for a [`SnapshotManifest`] of its current committed state.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotManifestRequest {
+    pub requested_by: PeerId,
+}
+
+/// Payload of the `Message::SnapshotManifestResponse` variant: `responder`'s answer to a
+/// [`SnapshotManifestRequest`].
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotManifestResponse {
+    pub manifest: SnapshotManifest,
+    pub responder: PeerId,
+}
+
+/// Payload of the `Message::SnapshotPartRequest` variant: ask `requested_by`'s peer for
+/// one [`SnapshotPart`] of the snapshot it advertised at `height`.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotPartRequest {
+    pub height: u64,
+    pub part_index: u32,
+    pub requested_by: PeerId,
+}
+
+/// Payload of the `Message::SnapshotPartResponse` variant: `responder`'s answer to a
+/// [`SnapshotPartRequest`].
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotPartResponse {
+    pub height: u64,
+    pub part: SnapshotPart,
+    pub responder: PeerId,
+}
+
+/// One hash-addressable chunk of a serialized [`WorldStateView`].
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotPart {
+    /// This part's position among [`SnapshotManifest::part_hashes`].
+    pub index: u32,
+    pub bytes: Vec<u8>,
+}
+
+impl SnapshotPart {
+    /// Hash of `self.bytes`, checked against the manifest's promised hash for this index.
+    pub fn hash(&self) -> Hash {
+        Hash::new(&self.bytes)
+    }
+}
+
+/// Describes a snapshot a synced peer is offering: the height/hash it was taken at, and
+/// the ordered hash of every part a requester must fetch to reconstruct it.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct SnapshotManifest {
+    pub height: u64,
+    pub block_hash: HashOf<VersionedCommittedBlock>,
+    pub part_hashes: Vec<Hash>,
+}
+
+/// Serialize `wsv` into [`SNAPSHOT_PART_SIZE_BYTES`]-sized parts and build the manifest
+/// describing them, tagged with the height/hash they correspond to.
+pub(super) fn build_snapshot(
+    wsv: &WorldStateView,
+    height: u64,
+    block_hash: HashOf<VersionedCommittedBlock>,
+) -> (SnapshotManifest, Vec<SnapshotPart>) {
+    let encoded = wsv.encode();
+    let parts: Vec<SnapshotPart> = encoded
+        .chunks(SNAPSHOT_PART_SIZE_BYTES)
+        .enumerate()
+        .map(|(index, bytes)| SnapshotPart {
+            index: index as u32,
+            bytes: bytes.to_vec(),
+        })
+        .collect();
+    let manifest = SnapshotManifest {
+        height,
+        block_hash,
+        part_hashes: parts.iter().map(SnapshotPart::hash).collect(),
+    };
+    (manifest, parts)
+}
+
+/// Reassemble a [`WorldStateView`] from `parts`, which must already be sorted by index and
+/// verified against the owning [`SnapshotManifest`]'s `part_hashes`.
+fn reassemble_snapshot(parts: &[SnapshotPart]) -> WorldStateView {
+    let mut encoded = Vec::new();
+    for part in parts {
+        encoded.extend_from_slice(&part.bytes);
+    }
+    WorldStateView::decode(&mut encoded.as_slice())
+        .expect("Snapshot parts already passed per-part hash verification; decoding must succeed.")
+}
+
+#[allow(clippy::expect_used)]
+fn online_peers<F: FaultInjection>(sumeragi: &SumeragiWithFault<F>) -> Vec<PeerId> {
+    sumeragi
+        .current_online_peers
+        .lock()
+        .expect("lock on online peers for snapshot sync")
+        .clone()
+}
+
+fn send_to(sumeragi: &SumeragiWithFault<impl FaultInjection>, peer: PeerId, message: Message) {
+    sumeragi.broker.issue_send_sync(&Post {
+        data: NetworkMessage::SumeragiMessage(Box::new(VersionedMessage::from(message))),
+        peer,
+    });
+}
+
+/// Answer a [`SnapshotManifestRequest`] with the current public-facing [`WorldStateView`]
+/// split into parts, tagged with `height`/`block_hash` as reported by the caller's
+/// [`SumeragiStateMachineData`] (the source of truth for where the committed chain is).
+pub(super) fn handle_manifest_request<F: FaultInjection>(
+    sumeragi: &SumeragiWithFault<F>,
+    height: u64,
+    block_hash: HashOf<VersionedCommittedBlock>,
+    request: SnapshotManifestRequest,
+) {
+    let wsv_guard = sumeragi.wsv_read();
+    let (manifest, _parts) = build_snapshot(&wsv_guard, height, block_hash);
+    drop(wsv_guard);
+
+    send_to(
+        sumeragi,
+        request.requested_by,
+        Message::from(SnapshotManifestResponse {
+            manifest,
+            responder: sumeragi.peer_id.clone(),
+        }),
+    );
+}
+
+/// Answer a [`SnapshotPartRequest`] with the snapshot part at `request.part_index`, taken
+/// from a fresh read of the current [`WorldStateView`]. No earlier snapshot is kept: if the
+/// local peer has since moved past `request.height`, the part is cut from the newer state,
+/// generally no longer matches the hash in the manifest that was handed out, and is
+/// rejected by the requester's per-part hash check.
+pub(super) fn handle_part_request<F: FaultInjection>(
+    sumeragi: &SumeragiWithFault<F>,
+    height: u64,
+    block_hash: HashOf<VersionedCommittedBlock>,
+    request: SnapshotPartRequest,
+) {
+    let wsv_guard = sumeragi.wsv_read();
+    let (_manifest, parts) = build_snapshot(&wsv_guard, height, block_hash);
+    drop(wsv_guard);
+
+    if let Some(part) = parts.into_iter().nth(request.part_index as usize) {
+        send_to(
+            sumeragi,
+            request.requested_by,
+            Message::from(SnapshotPartResponse {
+                height,
+                part,
+                responder: sumeragi.peer_id.clone(),
+            }),
+        );
+    }
+}
+
+/// Try to catch up via snapshot sync instead of genesis listening / full block replay.
+///
+/// Asks a random online peer for a [`SnapshotManifest`], then fetches its parts in
+/// parallel from whichever online peers answer first, re-requesting a part from a
+/// different peer whenever its hash doesn't match what the manifest promised. Returns
+/// `true` and leaves `state_machine_guard` caught up to the snapshot height if a peer
+/// offered one within [`SNAPSHOT_SYNC_TIMEOUT`]; returns `false` (leaving
+/// `state_machine_guard` untouched) if no peer is currently offering a snapshot, so the
+/// caller can fall back to its normal init path.
+#[allow(clippy::expect_used)]
+pub(super) fn try_sync_from_snapshot<F: FaultInjection>(
+    sumeragi: &SumeragiWithFault<F>,
+    state_machine_guard: &mut SumeragiStateMachineData,
+    incoming_message_receiver: &mut mpsc::Receiver<Message>,
+    shutdown_receiver: &mut tokio::sync::oneshot::Receiver<()>,
+) -> bool {
+    let Some(peer) = online_peers(sumeragi)
+        .choose(&mut *sumeragi.rng.lock())
+        .cloned()
+    else {
+        return false;
+    };
+
+    trace!(%peer, "Requesting a state snapshot before falling back to genesis listening.");
+    send_to(
+        sumeragi,
+        peer,
+        Message::from(SnapshotManifestRequest {
+            requested_by: sumeragi.peer_id.clone(),
+        }),
+    );
+
+    let deadline = Instant::now() + SNAPSHOT_SYNC_TIMEOUT;
+    let manifest = loop {
+        if shutdown_receiver.try_recv().is_ok() {
+            return false;
+        }
+        if Instant::now() > deadline {
+            trace!("No peer offered a state snapshot in time; falling back.");
+            return false;
+        }
+        match incoming_message_receiver.try_recv() {
+            Ok(Message::SnapshotManifestResponse(response)) => break response.manifest,
+            Ok(_unrelated) => continue,
+            Err(mpsc::error::TryRecvError::Empty) => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                panic!("Sumeragi message pump disconnected.")
+            }
+        }
+    };
+
+    let mut parts: Vec<Option<SnapshotPart>> = vec![None; manifest.part_hashes.len()];
+    let mut outstanding: Vec<u32> = (0..manifest.part_hashes.len() as u32).collect();
+    // Shifts the round-robin by one peer each round, so a part that is still outstanding
+    // (bad hash or no answer) is asked of a different peer than last time.
+    let mut round = 0_usize;
+
+    while !outstanding.is_empty() {
+        if shutdown_receiver.try_recv().is_ok() {
+            return false;
+        }
+        let candidates = online_peers(sumeragi);
+        if candidates.is_empty() {
+            std::thread::sleep(Duration::from_millis(50));
+            continue;
+        }
+        // Spread requests round-robin over the online peers so parts download in
+        // parallel instead of all landing on a single peer.
+        for (request_number, &index) in outstanding.iter().enumerate() {
+            let peer = candidates[(round + request_number) % candidates.len()].clone();
+            send_to(
+                sumeragi,
+                peer,
+                Message::from(SnapshotPartRequest {
+                    height: manifest.height,
+                    part_index: index,
+                    requested_by: sumeragi.peer_id.clone(),
+                }),
+            );
+        }
+        round += 1;
+
+        let part_deadline = Instant::now() + SNAPSHOT_SYNC_TIMEOUT;
+        while Instant::now() < part_deadline && !outstanding.is_empty() {
+            match incoming_message_receiver.try_recv() {
+                Ok(Message::SnapshotPartResponse(response)) => {
+                    let Some(expected_hash) =
+                        manifest.part_hashes.get(response.part.index as usize)
+                    else {
+                        continue;
+                    };
+                    if response.part.hash() != *expected_hash {
+                        warn!(
+                            part_index = response.part.index,
+                            "Snapshot part failed hash verification; will re-request from another peer."
+                        );
+                        continue;
+                    }
+                    let index = response.part.index as usize;
+                    if parts[index].is_none() {
+                        parts[index] = Some(response.part);
+                        outstanding.retain(|&i| i as usize != index);
+                    }
+                }
+                Ok(_unrelated) => continue,
+                Err(mpsc::error::TryRecvError::Empty) => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    panic!("Sumeragi message pump disconnected.")
+                }
+            }
+        }
+    }
+
+    let parts: Vec<SnapshotPart> = parts
+        .into_iter()
+        .map(|part| part.expect("all indices cleared from `outstanding` before loop exit"))
+        .collect();
+    let wsv = reassemble_snapshot(&parts);
+
+    state_machine_guard.wsv = wsv;
+    state_machine_guard.latest_block_height = manifest.height;
+    state_machine_guard.latest_block_hash = manifest.block_hash;
+    // The import queue worker's `wsv` is otherwise only ever advanced by `import_block`
+    // applying blocks one at a time; without this it would stay at its pre-sync (likely
+    // genesis-less) state and reject every block queued after we return.
+    sumeragi.block_import.resync(state_machine_guard.wsv.clone());
+
+    info!(
+        height = manifest.height,
+        "Caught up via state snapshot; replaying only blocks committed after it."
+    );
+    true
+}
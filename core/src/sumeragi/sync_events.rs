@@ -0,0 +1,54 @@
+//! Connectivity transitions derived by diffing the online-peer set, instead of consumers
+//! having to re-poll [`SumeragiWithFault::current_online_peers`] and recompute the delta
+//! themselves.
+
+use std::collections::HashSet;
+
+use iroha_data_model::prelude::*;
+use tokio::sync::broadcast;
+
+/// Number of in-flight [`SyncEvent`]s a lagging subscriber can fall behind by before older
+/// ones are dropped for it.
+const SYNC_EVENTS_CHANNEL_CAPACITY: usize = 32;
+
+/// A connectivity transition in the online-peer set.
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    /// `peer` was not online before this poll and is now.
+    PeerConnected(PeerId),
+    /// `peer` was online before this poll and no longer is.
+    PeerDisconnected(PeerId),
+}
+
+/// Sending half of the sync-event broadcast channel.
+pub type SyncEventSender = broadcast::Sender<SyncEvent>;
+
+/// Receiving half of the sync-event broadcast channel, obtained via
+/// [`super::Sumeragi::subscribe_to_sync_events`].
+pub type SyncEventReceiver = broadcast::Receiver<SyncEvent>;
+
+/// Build a fresh sync-event channel.
+pub fn channel() -> SyncEventSender {
+    let (sender, _receiver) = broadcast::channel(SYNC_EVENTS_CHANNEL_CAPACITY);
+    sender
+}
+
+/// Replace `current` with `new_peers`, emitting a [`SyncEvent`] on `sender` for every peer
+/// that joined or left rather than silently overwriting the old set.
+pub(super) fn diff_and_replace(
+    current: &mut Vec<PeerId>,
+    new_peers: Vec<PeerId>,
+    sender: &SyncEventSender,
+) {
+    let previous: HashSet<&PeerId> = current.iter().collect();
+    let incoming: HashSet<&PeerId> = new_peers.iter().collect();
+
+    for peer in incoming.difference(&previous) {
+        let _ = sender.send(SyncEvent::PeerConnected((*peer).clone()));
+    }
+    for peer in previous.difference(&incoming) {
+        let _ = sender.send(SyncEvent::PeerDisconnected((*peer).clone()));
+    }
+
+    *current = new_peers;
+}
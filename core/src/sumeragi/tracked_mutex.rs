@@ -0,0 +1,370 @@
+//! Debug-only lock-order verifier for `SumeragiWithFault`'s several independent
+//! [`Mutex`]es and [`RwLock`](parking_lot::RwLock)s. A single accidental recursive or
+//! out-of-order acquisition among them would deadlock the consensus thread silently;
+//! [`TrackedMutex`]/[`TrackedRwLock`] turn that into an immediate panic in debug builds,
+//! naming both locks involved. In release builds they compile down to the plain
+//! [`Mutex`]/[`RwLock`](parking_lot::RwLock) with no tracking overhead.
+
+#[cfg(debug_assertions)]
+mod imp {
+    use std::{
+        cell::RefCell,
+        sync::{LockResult, Mutex, MutexGuard, PoisonError},
+    };
+
+    use parking_lot::RwLock;
+
+    thread_local! {
+        /// Ids of the `TrackedMutex`/`TrackedRwLock`s this thread currently holds, innermost
+        /// last.
+        static HELD_LOCKS: RefCell<Vec<(&'static str, u32)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn push_held_lock(name: &'static str, order: u32) {
+        HELD_LOCKS.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(&(top_name, top_order)) = held.last() {
+                assert!(
+                    order > top_order,
+                    "Sumeragi lock order violation: attempted to lock `{name}` (order {order}) while this thread already holds `{top_name}` (order {top_order})",
+                );
+            }
+            held.push((name, order));
+        });
+    }
+
+    fn pop_held_lock(name: &'static str) {
+        HELD_LOCKS.with(|held| {
+            let popped = held.borrow_mut().pop();
+            debug_assert_eq!(
+                popped.map(|(name, _)| name),
+                Some(name),
+                "Sumeragi lock-order stack corrupted: popped a different lock than the one being dropped"
+            );
+        });
+    }
+
+    /// A [`Mutex`] tagged with a name and a fixed position (`order`) in a single global
+    /// lock ordering. Acquiring it while already holding a `TrackedMutex` whose `order`
+    /// is not strictly smaller panics on this thread.
+    #[derive(Debug)]
+    pub struct TrackedMutex<T> {
+        name: &'static str,
+        order: u32,
+        inner: Mutex<T>,
+    }
+
+    impl<T> TrackedMutex<T> {
+        /// Wrap `value` behind a lock tagged `name` at position `order`. Two
+        /// `TrackedMutex`es that can ever be held at the same time must use distinct,
+        /// consistently ordered `order`s.
+        pub const fn new(name: &'static str, order: u32, value: T) -> Self {
+            Self {
+                name,
+                order,
+                inner: Mutex::new(value),
+            }
+        }
+
+        /// Lock `self`, asserting it comes after everything this thread already holds.
+        ///
+        /// # Panics
+        /// If this thread already holds `self` (recursive acquisition) or a
+        /// `TrackedMutex` with an `order` greater than or equal to `self`'s.
+        pub fn lock(&self) -> LockResult<TrackedMutexGuard<'_, T>> {
+            push_held_lock(self.name, self.order);
+
+            match self.inner.lock() {
+                Ok(guard) => Ok(TrackedMutexGuard {
+                    name: self.name,
+                    guard,
+                }),
+                Err(poisoned) => Err(PoisonError::new(TrackedMutexGuard {
+                    name: self.name,
+                    guard: poisoned.into_inner(),
+                })),
+            }
+        }
+    }
+
+    /// Guard returned by [`TrackedMutex::lock`]. Pops this lock's id off the
+    /// thread-local held-lock stack on drop.
+    pub struct TrackedMutexGuard<'a, T> {
+        name: &'static str,
+        guard: MutexGuard<'a, T>,
+    }
+
+    impl<T> std::ops::Deref for TrackedMutexGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.guard
+        }
+    }
+
+    impl<T> std::ops::DerefMut for TrackedMutexGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.guard
+        }
+    }
+
+    impl<T> Drop for TrackedMutexGuard<'_, T> {
+        fn drop(&mut self) {
+            pop_held_lock(self.name);
+        }
+    }
+
+    /// A [`RwLock`](parking_lot::RwLock) tagged with a name and a fixed position (`order`)
+    /// in the same global lock ordering [`TrackedMutex`] participates in. Acquiring it
+    /// (read or upgradable-read) while already holding a tracked lock whose `order` is not
+    /// strictly smaller panics on this thread.
+    #[derive(Debug)]
+    pub struct TrackedRwLock<T> {
+        name: &'static str,
+        order: u32,
+        inner: RwLock<T>,
+    }
+
+    impl<T> TrackedRwLock<T> {
+        /// Wrap `value` behind a lock tagged `name` at position `order`. Two tracked locks
+        /// that can ever be held at the same time must use distinct, consistently ordered
+        /// `order`s.
+        pub const fn new(name: &'static str, order: u32, value: T) -> Self {
+            Self {
+                name,
+                order,
+                inner: RwLock::new(value),
+            }
+        }
+
+        /// Take a shared read lock, asserting it comes after everything this thread
+        /// already holds.
+        ///
+        /// # Panics
+        /// If this thread already holds a tracked lock with an `order` greater than or
+        /// equal to `self`'s.
+        pub fn read(&self) -> TrackedRwLockReadGuard<'_, T> {
+            push_held_lock(self.name, self.order);
+            TrackedRwLockReadGuard {
+                name: self.name,
+                guard: self.inner.read(),
+            }
+        }
+
+        /// Take an upgradable read lock, asserting it comes after everything this thread
+        /// already holds.
+        ///
+        /// # Panics
+        /// If this thread already holds a tracked lock with an `order` greater than or
+        /// equal to `self`'s.
+        pub fn upgradable_read(&self) -> TrackedRwLockUpgradableReadGuard<'_, T> {
+            push_held_lock(self.name, self.order);
+            TrackedRwLockUpgradableReadGuard {
+                name: self.name,
+                guard: Some(self.inner.upgradable_read()),
+            }
+        }
+    }
+
+    /// Guard returned by [`TrackedRwLock::read`]. Pops this lock's id off the thread-local
+    /// held-lock stack on drop.
+    pub struct TrackedRwLockReadGuard<'a, T> {
+        name: &'static str,
+        guard: parking_lot::RwLockReadGuard<'a, T>,
+    }
+
+    impl<T> std::ops::Deref for TrackedRwLockReadGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.guard
+        }
+    }
+
+    impl<T> Drop for TrackedRwLockReadGuard<'_, T> {
+        fn drop(&mut self) {
+            pop_held_lock(self.name);
+        }
+    }
+
+    /// Guard returned by [`TrackedRwLock::upgradable_read`]. [`Self::upgrade`] promotes it
+    /// to a [`TrackedRwLockWriteGuard`] covered by the same held-lock stack entry; otherwise
+    /// dropping it pops that entry same as any other tracked guard.
+    pub struct TrackedRwLockUpgradableReadGuard<'a, T> {
+        name: &'static str,
+        guard: Option<parking_lot::RwLockUpgradableReadGuard<'a, T>>,
+    }
+
+    impl<'a, T> TrackedRwLockUpgradableReadGuard<'a, T> {
+        /// Promote to a write guard. The held-lock stack entry pushed by
+        /// [`TrackedRwLock::upgradable_read`] already covers this acquisition, so promoting
+        /// doesn't touch the stack.
+        pub fn upgrade(mut self) -> TrackedRwLockWriteGuard<'a, T> {
+            let guard = self
+                .guard
+                .take()
+                .expect("guard is only taken here or in `Drop`, and `self` is consumed by this call");
+            TrackedRwLockWriteGuard {
+                name: self.name,
+                guard: parking_lot::RwLockUpgradableReadGuard::upgrade(guard),
+            }
+        }
+    }
+
+    impl<T> std::ops::Deref for TrackedRwLockUpgradableReadGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            self.guard
+                .as_ref()
+                .expect("guard is only taken in `Self::upgrade`, which consumes `self`")
+        }
+    }
+
+    impl<T> Drop for TrackedRwLockUpgradableReadGuard<'_, T> {
+        fn drop(&mut self) {
+            if self.guard.is_some() {
+                pop_held_lock(self.name);
+            }
+        }
+    }
+
+    /// Guard returned by [`TrackedRwLockUpgradableReadGuard::upgrade`]. Pops this lock's id
+    /// off the thread-local held-lock stack on drop.
+    pub struct TrackedRwLockWriteGuard<'a, T> {
+        name: &'static str,
+        guard: parking_lot::RwLockWriteGuard<'a, T>,
+    }
+
+    impl<T> std::ops::Deref for TrackedRwLockWriteGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.guard
+        }
+    }
+
+    impl<T> std::ops::DerefMut for TrackedRwLockWriteGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.guard
+        }
+    }
+
+    impl<T> Drop for TrackedRwLockWriteGuard<'_, T> {
+        fn drop(&mut self) {
+            pop_held_lock(self.name);
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+mod imp {
+    use std::sync::{LockResult, Mutex, MutexGuard};
+
+    use parking_lot::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
+
+    /// Release-mode [`TrackedMutex`]: a plain [`Mutex`] with the `name`/`order` tags
+    /// discarded at compile time.
+    #[derive(Debug)]
+    pub struct TrackedMutex<T>(Mutex<T>);
+
+    /// Release-mode guard: a plain [`MutexGuard`].
+    pub type TrackedMutexGuard<'a, T> = MutexGuard<'a, T>;
+
+    impl<T> TrackedMutex<T> {
+        pub const fn new(_name: &'static str, _order: u32, value: T) -> Self {
+            Self(Mutex::new(value))
+        }
+
+        pub fn lock(&self) -> LockResult<TrackedMutexGuard<'_, T>> {
+            self.0.lock()
+        }
+    }
+
+    /// Release-mode [`TrackedRwLock`]: a plain [`RwLock`](parking_lot::RwLock) with the
+    /// `name`/`order` tags discarded at compile time.
+    #[derive(Debug)]
+    pub struct TrackedRwLock<T>(RwLock<T>);
+
+    /// Release-mode read guard: a plain [`RwLockReadGuard`].
+    pub type TrackedRwLockReadGuard<'a, T> = RwLockReadGuard<'a, T>;
+
+    impl<T> TrackedRwLock<T> {
+        pub const fn new(_name: &'static str, _order: u32, value: T) -> Self {
+            Self(RwLock::new(value))
+        }
+
+        pub fn read(&self) -> TrackedRwLockReadGuard<'_, T> {
+            self.0.read()
+        }
+
+        pub fn upgradable_read(&self) -> TrackedRwLockUpgradableReadGuard<'_, T> {
+            TrackedRwLockUpgradableReadGuard(self.0.upgradable_read())
+        }
+    }
+
+    /// Release-mode upgradable guard: wraps a plain [`RwLockUpgradableReadGuard`] purely so
+    /// [`Self::upgrade`] has the same call-site shape as the debug-mode tracked guard.
+    pub struct TrackedRwLockUpgradableReadGuard<'a, T>(RwLockUpgradableReadGuard<'a, T>);
+
+    impl<'a, T> TrackedRwLockUpgradableReadGuard<'a, T> {
+        pub fn upgrade(self) -> TrackedRwLockWriteGuard<'a, T> {
+            TrackedRwLockWriteGuard(RwLockUpgradableReadGuard::upgrade(self.0))
+        }
+    }
+
+    impl<T> std::ops::Deref for TrackedRwLockUpgradableReadGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    /// Release-mode write guard: wraps a plain [`RwLockWriteGuard`].
+    pub struct TrackedRwLockWriteGuard<'a, T>(RwLockWriteGuard<'a, T>);
+
+    impl<T> std::ops::Deref for TrackedRwLockWriteGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T> std::ops::DerefMut for TrackedRwLockWriteGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            &mut self.0
+        }
+    }
+}
+
+pub use imp::{
+    TrackedMutex, TrackedMutexGuard, TrackedRwLock, TrackedRwLockReadGuard,
+    TrackedRwLockUpgradableReadGuard, TrackedRwLockWriteGuard,
+};
+
+/// Fixed global lock ordering for `SumeragiWithFault`'s [`TrackedMutex`]es. A lock may
+/// only be acquired while holding locks with a strictly smaller position.
+pub mod lock_order {
+    /// [`super::super::SumeragiWithFault::incoming_message_receiver`]. `lock`ed once at the
+    /// top of `run_sumeragi_main_loop` and held by that guard binding for the entire main
+    /// loop (every other lock below is acquired and released per iteration, nested inside
+    /// it), so this must be the outermost, lowest-order lock of all.
+    pub const INCOMING_MESSAGE_RECEIVER: u32 = 1;
+    /// [`super::super::SumeragiWithFault::sumeragi_state_machine_data`]. Ordered before
+    /// [`WSV`], so [`WSV`] may be acquired while this is held.
+    pub const SUMERAGI_STATE_MACHINE_DATA: u32 = 5;
+    /// [`super::super::SumeragiWithFault::wsv`]. Acquired as a read guard, or as an
+    /// upgradable read guard that the consensus thread promotes to a write guard via
+    /// [`super::TrackedRwLockUpgradableReadGuard::upgrade`] to publish a new state.
+    pub const WSV: u32 = 6;
+    /// [`super::super::SumeragiWithFault::current_online_peers`].
+    pub const CURRENT_ONLINE_PEERS: u32 = 10;
+    /// [`super::super::SumeragiWithFault::peer_block_heights`].
+    pub const PEER_BLOCK_HEIGHTS: u32 = 11;
+    /// [`super::super::SumeragiWithFault::latest_block_hash_for_use_by_block_sync`].
+    pub const LATEST_BLOCK_HASH_FOR_USE_BY_BLOCK_SYNC: u32 = 20;
+    /// [`super::super::SumeragiWithFault::incoming_message_sender`].
+    pub const INCOMING_MESSAGE_SENDER: u32 = 30;
+}
@@ -7,7 +7,7 @@ use std::collections::BTreeSet;
 use derive_more::Display;
 use getset::Getters;
 use iroha_data_model_derive::IdEqOrdHash;
-use iroha_schema::IntoSchema;
+use iroha_schema::{IntoSchema, Metadata, NamedFieldsMeta, UnnamedFieldsMeta};
 use parity_scale_codec::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
@@ -71,6 +71,262 @@ impl PermissionToken {
             payload,
         }
     }
+
+    /// Check that [`Self::payload`] is a well-formed SCALE encoding of the shape
+    /// `def.metadata` describes, and that `self` actually belongs to `def`.
+    ///
+    /// # Errors
+    /// - [`ValidationError::DefinitionIdMismatch`] if `self.definition_id != def.id`
+    /// - [`ValidationError::MalformedSchema`] if `def.metadata` isn't valid schema JSON
+    /// - Any other [`ValidationError`] variant if `self.payload` doesn't decode
+    ///   according to that schema
+    pub fn validate(&self, def: &PermissionTokenDefinition) -> Result<(), ValidationError> {
+        if self.definition_id != def.id {
+            return Err(ValidationError::DefinitionIdMismatch {
+                actual: self.definition_id.clone(),
+                expected: def.id.clone(),
+            });
+        }
+
+        let schema: iroha_schema::MetaMap = serde_json::from_str(&def.metadata)
+            .map_err(|err| ValidationError::MalformedSchema(err.to_string()))?;
+
+        let mut cursor = schema_walk::Cursor::new(&self.payload);
+        schema_walk::validate(&schema, &def.id, &mut cursor, "$")?;
+
+        let remaining = cursor.remaining();
+        if remaining > 0 {
+            return Err(ValidationError::TrailingBytes(remaining));
+        }
+        Ok(())
+    }
+}
+
+/// Error produced by [`PermissionToken::validate`].
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// [`PermissionToken::definition_id`] doesn't match [`PermissionTokenDefinition::id`]
+    #[display(
+        fmt = "permission token definition id mismatch: token has `{actual}`, expected `{expected}`"
+    )]
+    DefinitionIdMismatch {
+        /// Id carried by the token
+        actual: PermissionTokenId,
+        /// Id of the definition being validated against
+        expected: PermissionTokenId,
+    },
+    /// [`PermissionTokenDefinition::metadata`] is not a valid [`iroha_schema::MetaMap`] JSON
+    #[display(fmt = "malformed permission token schema metadata: {_0}")]
+    MalformedSchema(String),
+    /// A type referenced by the schema has no entry in the [`iroha_schema::MetaMap`]
+    #[display(fmt = "unknown type `{_0}` referenced by schema")]
+    UnknownType(String),
+    /// The payload ended before the schema said decoding should finish
+    #[display(fmt = "unexpected end of payload at `{path}`")]
+    UnexpectedEof {
+        /// Dotted path to the field being decoded when the payload ran out
+        path: String,
+    },
+    /// The payload has bytes left over after decoding finished
+    #[display(fmt = "trailing data in payload: {_0} byte(s) left after decoding")]
+    TrailingBytes(usize),
+    /// A `Compact` length prefix claims more bytes than remain in the payload
+    #[display(fmt = "length at `{path}` exceeds remaining payload")]
+    LengthOutOfBounds {
+        /// Dotted path to the offending collection
+        path: String,
+    },
+    /// An enum discriminant fell outside the range the schema declares for it
+    #[display(fmt = "invalid enum discriminant `{discriminant}` at `{path}`")]
+    BadDiscriminant {
+        /// Dotted path to the enum field
+        path: String,
+        /// The out-of-range discriminant byte
+        discriminant: u8,
+    },
+    /// A field's runtime shape didn't match its declared schema type
+    #[display(fmt = "type mismatch at `{path}`: {reason}")]
+    FieldTypeMismatch {
+        /// Dotted path to the offending field
+        path: String,
+        /// Human-readable description of the mismatch
+        reason: String,
+    },
+}
+
+/// Walks a [`parity_scale_codec`] payload against an [`iroha_schema::MetaMap`] without
+/// fully decoding it into a concrete Rust type, used by [`PermissionToken::validate`].
+mod schema_walk {
+    use iroha_schema::{Metadata, MetaMap};
+    use parity_scale_codec::Decode;
+
+    use super::ValidationError;
+
+    /// Tracks how much of the payload has been consumed so callers can detect
+    /// [`ValidationError::TrailingBytes`] once the top-level type is fully walked.
+    pub(super) struct Cursor<'payload> {
+        bytes: &'payload [u8],
+    }
+
+    impl<'payload> Cursor<'payload> {
+        pub(super) fn new(bytes: &'payload [u8]) -> Self {
+            Self { bytes }
+        }
+
+        pub(super) fn remaining(&self) -> usize {
+            self.bytes.len()
+        }
+
+        fn take(&mut self, len: usize, path: &str) -> Result<&'payload [u8], ValidationError> {
+            if len > self.bytes.len() {
+                return Err(ValidationError::UnexpectedEof {
+                    path: path.to_owned(),
+                });
+            }
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            Ok(taken)
+        }
+
+        fn take_compact_len(&mut self, path: &str) -> Result<usize, ValidationError> {
+            let len = parity_scale_codec::Compact::<u64>::decode(&mut self.bytes)
+                .map_err(|_| ValidationError::UnexpectedEof {
+                    path: path.to_owned(),
+                })?
+                .0 as usize;
+            if len > self.bytes.len() {
+                return Err(ValidationError::LengthOutOfBounds {
+                    path: path.to_owned(),
+                });
+            }
+            Ok(len)
+        }
+    }
+
+    fn lookup<'schema>(
+        schema: &'schema MetaMap,
+        type_id: &str,
+    ) -> Result<&'schema Metadata, ValidationError> {
+        // `MetaMap` derefs to its underlying `Ident -> Metadata` map.
+        schema
+            .get(type_id)
+            .ok_or_else(|| ValidationError::UnknownType(type_id.to_owned()))
+    }
+
+    /// Recursively validate that `cursor` decodes as `type_id` according to `schema`,
+    /// consuming exactly the bytes that type occupies.
+    pub(super) fn validate(
+        schema: &MetaMap,
+        type_id: &str,
+        cursor: &mut Cursor,
+        path: &str,
+    ) -> Result<(), ValidationError> {
+        match lookup(schema, type_id)? {
+            Metadata::Bool => {
+                cursor.take(1, path)?;
+                Ok(())
+            }
+            Metadata::Int(mode) => {
+                cursor.take(mode.size_in_bytes(), path)?;
+                Ok(())
+            }
+            Metadata::String => {
+                let len = cursor.take_compact_len(path)?;
+                let bytes = cursor.take(len, path)?;
+                core::str::from_utf8(bytes).map_err(|_| ValidationError::FieldTypeMismatch {
+                    path: path.to_owned(),
+                    reason: "string field is not valid UTF-8".to_owned(),
+                })?;
+                Ok(())
+            }
+            Metadata::Option(inner) => {
+                let discriminant = cursor.take(1, path)?[0];
+                match discriminant {
+                    0 => Ok(()),
+                    1 => validate(schema, inner, cursor, &format!("{path}.some")),
+                    other => Err(ValidationError::BadDiscriminant {
+                        path: path.to_owned(),
+                        discriminant: other,
+                    }),
+                }
+            }
+            Metadata::Vec(item) => {
+                let len = cursor.take_compact_len(path)?;
+                for i in 0..len {
+                    validate(schema, item, cursor, &format!("{path}[{i}]"))?;
+                }
+                Ok(())
+            }
+            Metadata::Array(array) => {
+                for i in 0..array.len {
+                    validate(schema, &array.ty, cursor, &format!("{path}[{i}]"))?;
+                }
+                Ok(())
+            }
+            Metadata::Tuple(UnnamedFieldsMeta { types }) => {
+                for (i, ty) in types.iter().enumerate() {
+                    validate(schema, ty, cursor, &format!("{path}.{i}"))?;
+                }
+                Ok(())
+            }
+            Metadata::Struct(NamedFieldsMeta { declarations }) => {
+                for field in declarations {
+                    validate(
+                        schema,
+                        &field.ty,
+                        cursor,
+                        &format!("{path}.{}", field.name),
+                    )?;
+                }
+                Ok(())
+            }
+            Metadata::Enum(enum_meta) => {
+                let discriminant = cursor.take(1, path)?[0];
+                let variant = enum_meta
+                    .variants
+                    .iter()
+                    .find(|variant| variant.discriminant == discriminant)
+                    .ok_or(ValidationError::BadDiscriminant {
+                        path: path.to_owned(),
+                        discriminant,
+                    })?;
+                if let Some(ty) = &variant.ty {
+                    validate(schema, ty, cursor, &format!("{path}::{}", variant.name))?;
+                }
+                Ok(())
+            }
+            Metadata::Map(map_meta) => {
+                let len = cursor.take_compact_len(path)?;
+                for i in 0..len {
+                    validate(schema, &map_meta.key, cursor, &format!("{path}[{i}].key"))?;
+                    validate(
+                        schema,
+                        &map_meta.value,
+                        cursor,
+                        &format!("{path}[{i}].value"),
+                    )?;
+                }
+                Ok(())
+            }
+            Metadata::Result(result_meta) => {
+                let discriminant = cursor.take(1, path)?[0];
+                match discriminant {
+                    0 => validate(schema, &result_meta.ok, cursor, &format!("{path}.ok")),
+                    1 => validate(schema, &result_meta.err, cursor, &format!("{path}.err")),
+                    other => Err(ValidationError::BadDiscriminant {
+                        path: path.to_owned(),
+                        discriminant: other,
+                    }),
+                }
+            }
+            Metadata::TupleStruct(UnnamedFieldsMeta { types }) => {
+                for (i, ty) in types.iter().enumerate() {
+                    validate(schema, ty, cursor, &format!("{path}.{i}"))?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 impl core::fmt::Display for PermissionToken {
@@ -85,5 +341,7 @@ impl Registered for PermissionTokenDefinition {
 
 pub mod prelude {
     //! The prelude re-exports most commonly used traits, structs and macros from this crate.
-    pub use super::{PermissionToken, PermissionTokenDefinition, PermissionTokenId};
+    pub use super::{
+        PermissionToken, PermissionTokenDefinition, PermissionTokenId, ValidationError,
+    };
 }
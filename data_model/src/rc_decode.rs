@@ -13,6 +13,10 @@ use crate::Identifiable;
 
 /// Deserialize map of `id -> identifiable` applying `f` as optimizer for keys and values.
 ///
+/// `f` runs only once `id == *value.id()` has already been checked, so the optimizer may
+/// rely on the key and the value's embedded id being equal — see [`share_id`] for the
+/// common case of collapsing them onto a single allocation.
+///
 /// # Errors
 ///
 /// - Input is not a map
@@ -32,6 +36,22 @@ where
     deserializer.deserialize_map(RefCountingVisitor(BTreeMap::default(), f))
 }
 
+/// Optimizer for [`deserialize_map_with`]: replace `value`'s embedded id with a clone of
+/// `key`'s allocation, so the two end up sharing one allocation instead of two.
+///
+/// Sound to call as soon as `*key == *value.id()` is guaranteed, which
+/// [`deserialize_map_with`] already checks before invoking its optimizer callback: the two
+/// allocations are byte-for-byte identical, so replacing one with a clone of the other
+/// can't change observable behaviour. When `K`/`V::Id` wrap an `iroha_primitives::conststr::ConstString`,
+/// this turns two `ArcStr` allocations into one.
+pub fn share_id<K, V>(key: &K, value: &mut V)
+where
+    K: Clone,
+    V: Identifiable<Id = K>,
+{
+    *value.id_mut() = key.clone();
+}
+
 struct RefCountingVisitor<K, V, F>(BTreeMap<K, V>, F);
 
 impl<'de, K, V, F> serde::de::Visitor<'de> for RefCountingVisitor<K, V, F>
@@ -64,3 +84,68 @@ where
         Ok(self.0)
     }
 }
+
+pub mod prelude {
+    //! The prelude re-exports most commonly used traits, structs and macros from this crate.
+    pub use super::{deserialize_map_with, share_id};
+}
+
+#[cfg(test)]
+mod tests {
+    use iroha_primitives::conststr::ConstString;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct Item {
+        id: ConstString,
+    }
+
+    impl Identifiable for Item {
+        type Id = ConstString;
+
+        fn id(&self) -> &Self::Id {
+            &self.id
+        }
+
+        fn id_mut(&mut self) -> &mut Self::Id {
+            &mut self.id
+        }
+    }
+
+    #[test]
+    fn deserialize_map_with_shares_id_allocation() {
+        let json = r#"{"a string long enough to never be inlined": {"id": "a string long enough to never be inlined"}}"#;
+        // `from_reader` rather than `from_str`: a zero-copy `from_str` deserializer hands
+        // the visitor a borrowed `&str` for both the key and the value's `id`, which
+        // `ConstString`'s `visit_borrowed_str` already interns — so the two would come out
+        // `ptr_eq` regardless of whether `share_id` ran. Going through `from_reader` forces
+        // `visit_str`, which allocates independently per call, so a shared allocation here
+        // can only be `share_id`'s doing.
+        let mut deserializer = serde_json::Deserializer::from_reader(json.as_bytes());
+        let map: BTreeMap<ConstString, Item> =
+            deserialize_map_with(&mut deserializer, share_id).expect("valid map");
+
+        let (key, value) = map.iter().next().expect("map has one entry");
+        assert!(
+            key.ptr_eq(&value.id),
+            "key and value id should share the same allocation after `share_id`"
+        );
+    }
+
+    #[test]
+    fn deserialize_map_without_share_id_keeps_distinct_allocations() {
+        let json = r#"{"a string long enough to never be inlined": {"id": "a string long enough to never be inlined"}}"#;
+        let mut deserializer = serde_json::Deserializer::from_reader(json.as_bytes());
+        let map: BTreeMap<ConstString, Item> =
+            deserialize_map_with(&mut deserializer, |_, _| {}).expect("valid map");
+
+        let (key, value) = map.iter().next().expect("map has one entry");
+        assert!(
+            !key.ptr_eq(&value.id),
+            "without `share_id`, key and value id should keep their independently \
+             deserialized allocations"
+        );
+    }
+}
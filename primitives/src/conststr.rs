@@ -26,23 +26,36 @@ use serde::{
 
 const MAX_INLINED_STRING_LEN: usize = 2 * size_of::<usize>() - 1;
 
+/// Marks the inlined variant. Aliases the most significant bit of the final byte
+/// of the union for every variant.
+const INLINE_TAG_BYTE: u8 = 0b1000_0000;
+/// Marks the non-allocating `'static` variant within the heap (tag-0) space.
+/// Aliases the second most significant bit of the final byte of the union.
+const STATIC_TAG_BYTE: u8 = 0b0100_0000;
+/// [`STATIC_TAG_BYTE`] shifted into position within [`StaticString::len`]/[`ArcString::len`].
+const STATIC_TAG_BIT: usize = (STATIC_TAG_BYTE as usize) << (usize::BITS - 8);
+
 /// Immutable inlinable string.
 /// Strings shorter than 15/7/3 bytes (in 64/32/16-bit architecture) are inlined.
-/// Union represents const-string variants: inlined or reference counted.
-/// Distinction between variants are achieved by tagging most significant bit of field `len`:
+/// Union represents const-string variants: inlined, reference counted or `'static`.
+/// Distinction between variants are achieved by tagging the most significant bits of field `len`:
 /// - for inlined variant MSB of `len` is always equal to 1, it's enforced by `InlinedString` constructor;
-/// - for reference counted variant MSB of `len` is always equal to 0, it's enforced by the fact
-/// that `Box` and `Vec` never allocate more than`isize::MAX bytes`.
+/// - for the two heap variants MSB of `len` is always equal to 0, it's enforced by the fact
+/// that `Box` and `Vec` never allocate more than `isize::MAX` bytes;
+/// - within the heap variants, the next bit distinguishes reference counted (0) from `'static` (1),
+/// which is sound because no real string length ever approaches `isize::MAX / 2`.
 /// For little-endian 64bit architecture memory layout of [`Self`] is following:
 ///
 /// ```text
-/// +-------------------+-------+---------+---------------------------+
-/// | Bits              | 0..63 | 64..118 | 119..126 | 127            |
-/// +-------------------+-------+---------+----------+----------------+
-/// | Inlined           | payload         | len      | tag (always 1) |
-/// +-------------------+-------+---------+---------------------------+
-/// | Reference counted | ptr   | len                | tag (always 0) |
-/// +-------------------+-------+--------------------+----------------+
+/// +-------------------+-------+---------+----------+---------+----------------+
+/// | Bits              | 0..63 | 64..119 | 120..125 | 126     | 127            |
+/// +-------------------+-------+---------+----------+---------+----------------+
+/// | Inlined           | payload         | len                | tag (always 1) |
+/// +-------------------+-------+---------+----------+---------+----------------+
+/// | Reference counted | ptr   | len                | sub (0) | tag (always 0) |
+/// +-------------------+-------+--------------------+---------+----------------+
+/// | Static            | ptr   | len                | sub (1) | tag (always 0) |
+/// +-------------------+-------+--------------------+---------+----------------+
 /// ```
 #[derive(DebugCustom, Display)]
 #[display(fmt = "{}", "&**self")]
@@ -51,10 +64,13 @@ const MAX_INLINED_STRING_LEN: usize = 2 * size_of::<usize>() - 1;
 pub union ConstString {
     inlined: InlinedString,
     ref_counted: ManuallyDrop<ArcString>,
+    static_str: StaticString,
 }
 
 /// Test to ensure at compile-time that all [`ConstString`] variants have the same size.
 const _: () = assert!(size_of::<InlinedString>() == size_of::<ManuallyDrop<ArcString>>());
+const _: () = assert!(size_of::<InlinedString>() == size_of::<StaticString>());
+const _: () = assert!(align_of::<InlinedString>() == align_of::<StaticString>());
 
 /// Test [`ConstString`] layout
 const _: () = assert!(size_of::<ConstString>() == size_of::<Box<str>>());
@@ -72,8 +88,11 @@ impl ConstString {
         if self.is_inlined() {
             // Safety: `is_inlined()` returned `true`
             unsafe { self.inlined().len() }
+        } else if self.is_static() {
+            // Safety: `is_static()` returned `true`
+            unsafe { self.static_str().len() }
         } else {
-            // Safety: `is_inlined()` returned `false`
+            // Safety: neither `is_inlined()` nor `is_static()` returned `true`
             unsafe { self.reference_counted().len() }
         }
     }
@@ -92,6 +111,29 @@ impl ConstString {
         }
     }
 
+    /// Construct [`Self`] from a `'static` string slice without allocating.
+    ///
+    /// Unlike `From<&str>`, this never touches the `ArcStr` allocator or its atomic
+    /// refcount: strings that fit inline are inlined as usual, and longer ones are
+    /// stored as a bare `(ptr, len)` pair borrowing the `'static` data. Prefer this
+    /// for compile-time identifiers such as well-known `Name`s or permission token ids.
+    #[inline]
+    #[allow(unsafe_code)]
+    pub const fn from_static(value: &'static str) -> Self {
+        if value.len() <= MAX_INLINED_STRING_LEN {
+            Self {
+                inlined: InlinedString::from_static_str(value),
+            }
+        } else {
+            Self {
+                static_str: StaticString {
+                    ptr: value.as_ptr(),
+                    len: value.len() | STATIC_TAG_BIT,
+                },
+            }
+        }
+    }
+
     /// Return `true` if [`Self`] is inlined.
     #[inline]
     #[allow(unsafe_code)]
@@ -102,6 +144,15 @@ impl ConstString {
         unsafe { self.inlined().is_inlined() }
     }
 
+    /// Return `true` if [`Self`] holds the non-allocating `'static` variant.
+    #[inline]
+    #[allow(unsafe_code)]
+    pub const fn is_static(&self) -> bool {
+        // Safety: reading the tag byte is always safe, see `is_inlined()`.
+        let tag_byte = unsafe { self.inlined().len };
+        tag_byte & INLINE_TAG_BYTE == 0 && tag_byte & STATIC_TAG_BYTE != 0
+    }
+
     #[allow(unsafe_code)]
     #[inline]
     const unsafe fn inlined(&self) -> &InlinedString {
@@ -113,12 +164,114 @@ impl ConstString {
     unsafe fn reference_counted(&self) -> &ArcString {
         &self.ref_counted
     }
+
+    #[allow(unsafe_code)]
+    #[inline]
+    const unsafe fn static_str(&self) -> &StaticString {
+        &self.static_str
+    }
+
+    /// Intern `value` in the process-global string pool, returning a [`Self`] that
+    /// shares the same `ArcStr` allocation with any other [`Self`] interned with the
+    /// same contents. Strings that fit inline are never interned, since they don't
+    /// allocate in the first place.
+    #[cfg(feature = "std")]
+    pub fn intern(value: &str) -> Self {
+        if value.len() <= MAX_INLINED_STRING_LEN {
+            return Self::from(value);
+        }
+        Self {
+            ref_counted: ManuallyDrop::new(ArcString {
+                len: value.len(),
+                arc: intern::intern(value),
+            }),
+        }
+    }
+
+    /// Reap interner pool entries that no live [`Self`] still references, i.e. whose
+    /// only remaining strong reference is the pool's own. Call this periodically;
+    /// it never invalidates a [`Self`] that is still alive.
+    #[cfg(feature = "std")]
+    pub fn intern_gc() {
+        intern::gc();
+    }
+
+    /// Return `true` if both `self` and `other` are heap-allocated (reference counted
+    /// or interned) and point at the same `ArcStr` allocation. Never true for inlined
+    /// or `'static` operands, even if their contents match.
+    #[inline]
+    #[allow(unsafe_code)]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        if self.is_inlined() || other.is_inlined() || self.is_static() || other.is_static() {
+            return false;
+        }
+        // Safety: neither operand is inlined or static, checked above.
+        unsafe {
+            ArcStr::ptr_eq(
+                &self.reference_counted().arc,
+                &other.reference_counted().arc,
+            )
+        }
+    }
+}
+
+/// Process-global interning pool for [`ConstString`]'s heap variant.
+#[cfg(feature = "std")]
+mod intern {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        sync::{Mutex, OnceLock},
+    };
+
+    use arcstr::ArcStr;
+
+    /// Sharded to reduce contention: each shard guards an independent `HashSet`.
+    const SHARD_COUNT: usize = 16;
+
+    struct Pool {
+        shards: [Mutex<HashSet<ArcStr>>; SHARD_COUNT],
+    }
+
+    static POOL: OnceLock<Pool> = OnceLock::new();
+
+    fn pool() -> &'static Pool {
+        POOL.get_or_init(|| Pool {
+            shards: std::array::from_fn(|_| Mutex::new(HashSet::default())),
+        })
+    }
+
+    fn shard_index(value: &str) -> usize {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        (hasher.finish() as usize) % SHARD_COUNT
+    }
+
+    pub(super) fn intern(value: &str) -> ArcStr {
+        let shard = &pool().shards[shard_index(value)];
+        let mut guard = shard.lock().expect("ConstString intern pool poisoned");
+        if let Some(existing) = guard.get(value) {
+            return existing.clone();
+        }
+        let arc = ArcStr::from(value);
+        guard.insert(arc.clone());
+        arc
+    }
+
+    pub(super) fn gc() {
+        for shard in &pool().shards {
+            let mut guard = shard.lock().expect("ConstString intern pool poisoned");
+            guard.retain(|arc| ArcStr::strong_count(arc) > 1);
+        }
+    }
 }
 
 impl<T: ?Sized> AsRef<T> for ConstString
 where
     InlinedString: AsRef<T>,
     ArcString: AsRef<T>,
+    StaticString: AsRef<T>,
 {
     #[inline]
     #[allow(unsafe_code)]
@@ -126,8 +279,11 @@ where
         if self.is_inlined() {
             // Safety: `is_inlined()` returned `true`
             unsafe { self.inlined().as_ref() }
+        } else if self.is_static() {
+            // Safety: `is_static()` returned `true`
+            unsafe { self.static_str().as_ref() }
         } else {
-            // Safety: `is_inlined()` returned `false`
+            // Safety: neither `is_inlined()` nor `is_static()` returned `true`
             unsafe { self.reference_counted().as_ref() }
         }
     }
@@ -173,7 +329,10 @@ impl PartialOrd for ConstString {
 impl PartialEq for ConstString {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        PartialEq::eq(&**self, &**other)
+        // Fast path: identical heap allocation implies equal contents, skip the
+        // byte-wise comparison. `ptr_eq` is `false` (not an error) for inlined/static
+        // operands, so the byte-wise fallback below still covers those correctly.
+        self.ptr_eq(other) || PartialEq::eq(&**self, &**other)
     }
 }
 
@@ -230,9 +389,16 @@ impl Clone for ConstString {
                     inlined: *self.inlined(),
                 }
             }
+        } else if self.is_static() {
+            // Safety: `is_static()` returned `true`. Trivial field copy, no atomic bump.
+            unsafe {
+                Self {
+                    static_str: *self.static_str(),
+                }
+            }
         } else {
             Self {
-                // Safety: `is_inlined()` returned `false`
+                // Safety: neither `is_inlined()` nor `is_static()` returned `true`
                 ref_counted: unsafe { ManuallyDrop::new(self.reference_counted().clone()) },
             }
         }
@@ -242,8 +408,8 @@ impl Clone for ConstString {
 impl Drop for ConstString {
     #[allow(unsafe_code)]
     fn drop(&mut self) {
-        if !self.is_inlined() {
-            // SAFETY: safe because `is_inlined()` returned `false`.
+        if !self.is_inlined() && !self.is_static() {
+            // SAFETY: safe because neither `is_inlined()` nor `is_static()` returned `true`.
             unsafe {
                 ManuallyDrop::drop(&mut self.ref_counted);
             }
@@ -268,7 +434,7 @@ impl<'de> Deserialize<'de> for ConstString {
 
 struct ConstStringVisitor;
 
-impl Visitor<'_> for ConstStringVisitor {
+impl<'de> Visitor<'de> for ConstStringVisitor {
     type Value = ConstString;
 
     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -279,9 +445,37 @@ impl Visitor<'_> for ConstStringVisitor {
         Ok(v.into())
     }
 
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        // Strings that fit inline never allocate, borrowed or not. Longer ones dedup
+        // against the interner pool, so a borrowed slice never has to go through a
+        // transient owned `String` just to be thrown away.
+        if v.len() <= MAX_INLINED_STRING_LEN {
+            Ok(v.into())
+        } else {
+            #[cfg(feature = "std")]
+            {
+                Ok(ConstString::intern(v))
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                Ok(v.into())
+            }
+        }
+    }
+
     fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
         Ok(v.into())
     }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let s = core::str::from_utf8(v).map_err(Error::custom)?;
+        self.visit_str(s)
+    }
+
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        let s = core::str::from_utf8(v).map_err(Error::custom)?;
+        self.visit_borrowed_str(s)
+    }
 }
 
 impl WrapperTypeEncode for ConstString {}
@@ -381,6 +575,40 @@ impl From<String> for ArcString {
     }
 }
 
+/// Non-allocating heap-variant borrowing a `'static` string slice.
+///
+/// `len` carries [`STATIC_TAG_BIT`] in addition to the actual length; use
+/// [`StaticString::len`] rather than reading the field directly.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct StaticString {
+    #[cfg(target_endian = "little")]
+    ptr: *const u8,
+    len: usize,
+    #[cfg(target_endian = "big")]
+    ptr: *const u8,
+}
+
+impl StaticString {
+    #[inline]
+    const fn len(&self) -> usize {
+        self.len & !STATIC_TAG_BIT
+    }
+}
+
+impl AsRef<str> for StaticString {
+    #[allow(unsafe_code)]
+    #[inline]
+    fn as_ref(&self) -> &str {
+        // Safety: `ptr`/`len` are only ever constructed in `ConstString::from_static`
+        // from a valid `&'static str`, so the slice is valid UTF-8 for `'static`.
+        unsafe {
+            let slice = core::slice::from_raw_parts(self.ptr, self.len());
+            core::str::from_utf8_unchecked(slice)
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 #[repr(C)]
 struct InlinedString {
@@ -411,6 +639,26 @@ impl InlinedString {
             len: 128,
         }
     }
+
+    /// Const-fn counterpart of `TryFrom<&str>`, used by `ConstString::from_static`.
+    /// Caller must guarantee `value.len() <= MAX_INLINED_STRING_LEN`.
+    #[allow(clippy::cast_possible_truncation)]
+    const fn from_static_str(value: &str) -> Self {
+        let bytes = value.as_bytes();
+        let len = bytes.len();
+        let mut payload = [0_u8; MAX_INLINED_STRING_LEN];
+        // `copy_from_slice` is not `const fn`, so copy byte-by-byte instead.
+        let mut i = 0;
+        while i < len {
+            payload[i] = bytes[i];
+            i += 1;
+        }
+        Self {
+            payload,
+            // Truncation can't happen: caller guarantees `len <= MAX_INLINED_STRING_LEN`.
+            len: 128 + len as u8,
+        }
+    }
 }
 
 // TODO: Not safe
@@ -510,6 +758,42 @@ mod tests {
                 assert_eq!(const_string, const_string_clone);
             });
         }
+
+        #[test]
+        fn const_string_from_static_len() {
+            run_with_static_strings(|string| {
+                let const_string = ConstString::from_static(string);
+                assert_eq!(const_string.len(), string.len());
+            });
+        }
+
+        #[test]
+        fn const_string_from_static_is_inlined_or_static() {
+            run_with_static_strings(|string| {
+                let const_string = ConstString::from_static(string);
+                let is_inlined = string.len() <= MAX_INLINED_STRING_LEN;
+                assert_eq!(const_string.is_inlined(), is_inlined, "with len {}", string.len());
+                assert_eq!(const_string.is_static(), !is_inlined, "with len {}", string.len());
+            });
+        }
+
+        #[test]
+        fn const_string_from_static_deref() {
+            run_with_static_strings(|string| {
+                let const_string = ConstString::from_static(string);
+                assert_eq!(&*const_string, string);
+            });
+        }
+
+        #[test]
+        #[allow(clippy::redundant_clone)]
+        fn const_string_from_static_clone() {
+            run_with_static_strings(|string| {
+                let const_string = ConstString::from_static(string);
+                let const_string_clone = const_string.clone();
+                assert_eq!(const_string, const_string_clone);
+            });
+        }
     }
 
     mod integration {
@@ -585,6 +869,37 @@ mod tests {
             });
         }
 
+        #[test]
+        fn const_string_intern_shares_allocation() {
+            // Long enough to skip the inlined variant on every architecture.
+            let a = ConstString::intern("a string long enough to never be inlined, hopefully");
+            let b = ConstString::intern("a string long enough to never be inlined, hopefully");
+            assert!(a.ptr_eq(&b));
+        }
+
+        #[test]
+        fn const_string_ptr_eq_false_for_distinct_allocations() {
+            let a = ConstString::from(
+                "a string long enough to never be inlined, hopefully".to_owned(),
+            );
+            let b = ConstString::from(
+                "a string long enough to never be inlined, hopefully".to_owned(),
+            );
+            assert!(!a.ptr_eq(&b));
+            assert_eq!(a, b);
+        }
+
+        #[test]
+        fn const_string_deserialize_borrowed() {
+            run_with_strings(|string| {
+                let json = serde_json::to_string(&string).expect("valid");
+                // `serde_json::from_str` hands the visitor a borrowed `&str` slice.
+                let const_string: ConstString =
+                    serde_json::from_str(&json).expect("valid json string");
+                assert_eq!(const_string, string);
+            });
+        }
+
         #[test]
         fn const_string_serde_serialize() {
             run_with_strings(|string| {
@@ -650,4 +965,59 @@ mod tests {
         .map(str::to_owned)
         .for_each(f);
     }
+
+    /// Same buckets as [`run_with_strings`], but kept as `&'static str` for
+    /// [`ConstString::from_static`] coverage.
+    fn run_with_static_strings(f: impl Fn(&'static str)) {
+        [
+            // 0-byte
+            "",
+            // 1-byte
+            "?",
+            // 2-bytes
+            "??",
+            "Δ",
+            // 3-bytes
+            "???",
+            "?Δ",
+            "ン",
+            // 4-bytes
+            "????",
+            "??Δ",
+            "ΔΔ",
+            "?ン",
+            "🔥",
+            // 7-bytes
+            "???????",
+            "???🔥",
+            "Δ?🔥",
+            "ン?ン",
+            // 8-bytes
+            "????????",
+            "ΔΔΔΔ",
+            "Δンン",
+            "🔥🔥",
+            // 15-bytes
+            "???????????????",
+            "?????????????Δ",
+            "????????????ン",
+            "???????????🔥",
+            "Δ?🔥Δンン",
+            // 16-bytes
+            "????????????????",
+            "????????Δンン",
+            "ΔΔΔΔΔΔΔΔ",
+            "🔥🔥🔥🔥",
+            // 30-bytes
+            "??????????????????????????????",
+            "??????????????????????????ΔΔ",
+            "Δ?🔥ΔンンΔ?🔥Δンン",
+            // 31-bytes
+            "???????????????????????Δンン",
+            "Δ?🔥Δンン🔥🔥🔥🔥",
+            "???????????????ΔΔΔΔΔΔΔΔ",
+        ]
+        .into_iter()
+        .for_each(f);
+    }
 }